@@ -0,0 +1,179 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+//! Allocation counting for tests
+//!
+//! Counts both Rust heap allocations (through a counting global allocator) and
+//! allocations cJSON makes through its hooks. Counters are per thread, so tests
+//! running in parallel do not disturb each other.
+//!
+//! The cJSON hooks are installed once, on the first Rust allocation. The test
+//! harness allocates before it starts any test thread, so the global hooks
+//! never change while cJSON is in use.
+//!
+//! The file only relies on the cJSON C API, so integration tests share it:
+//!
+//! ```ignore
+//! #[path = "../src/alloc_counter.rs"]
+//! mod alloc_counter;
+//! ```
+
+#![allow(dead_code)]
+
+extern crate std;
+
+use core::cell::Cell;
+use core::ffi::c_void;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::Once;
+
+/// Allocations made while running a closure
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct AllocStats {
+    /// Rust heap allocations
+    pub rust_allocs: usize,
+    /// Rust heap bytes allocated, freed or not
+    pub rust_bytes: usize,
+    /// Rust heap bytes still allocated at the end
+    pub rust_live_bytes: isize,
+    /// cJSON allocations
+    pub c_allocs: usize,
+    /// cJSON allocations not yet freed at the end
+    pub c_live: isize,
+}
+
+std::thread_local! {
+    static RUST_ALLOCS: Cell<usize> = const { Cell::new(0) };
+    static RUST_BYTES: Cell<usize> = const { Cell::new(0) };
+    static RUST_LIVE_BYTES: Cell<isize> = const { Cell::new(0) };
+    static C_ALLOCS: Cell<usize> = const { Cell::new(0) };
+    static C_LIVE: Cell<isize> = const { Cell::new(0) };
+    /// cJSON allocations left to succeed before one fails
+    static C_ALLOCS_LEFT: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // Installing the hooks does not allocate, so this cannot recurse
+        INSTALL_HOOKS.call_once(install_hooks);
+        // try_with: the thread-local may already be gone during thread teardown
+        let _ = RUST_ALLOCS.try_with(|n| n.set(n.get() + 1));
+        let _ = RUST_BYTES.try_with(|n| n.set(n.get() + layout.size()));
+        let _ = RUST_LIVE_BYTES.try_with(|n| n.set(n.get() + layout.size() as isize));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let _ = RUST_LIVE_BYTES.try_with(|n| n.set(n.get() - layout.size() as isize));
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Same layout as `cJSON_Hooks`
+#[repr(C)]
+struct Hooks {
+    malloc_fn: Option<unsafe extern "C" fn(size: usize) -> *mut c_void>,
+    free_fn: Option<unsafe extern "C" fn(ptr: *mut c_void)>,
+}
+
+unsafe extern "C" {
+    #[link_name = "cJSON_InitHooks"]
+    fn init_hooks(hooks: *mut Hooks);
+    fn malloc(size: usize) -> *mut c_void;
+    fn free(ptr: *mut c_void);
+}
+
+unsafe extern "C" fn counting_malloc(size: usize) -> *mut c_void {
+    let fail = C_ALLOCS_LEFT
+        .try_with(|left| match left.get() {
+            Some(0) => {
+                left.set(None);
+                true
+            }
+            Some(n) => {
+                left.set(Some(n - 1));
+                false
+            }
+            None => false,
+        })
+        .unwrap_or(false);
+    if fail {
+        return core::ptr::null_mut();
+    }
+    let _ = C_ALLOCS.try_with(|n| n.set(n.get() + 1));
+    let _ = C_LIVE.try_with(|n| n.set(n.get() + 1));
+    unsafe { malloc(size) }
+}
+
+unsafe extern "C" fn counting_free(ptr: *mut c_void) {
+    if !ptr.is_null() {
+        let _ = C_LIVE.try_with(|n| n.set(n.get() - 1));
+    }
+    unsafe { free(ptr) }
+}
+
+static INSTALL_HOOKS: Once = Once::new();
+
+fn install_hooks() {
+    let mut hooks = Hooks {
+        malloc_fn: Some(counting_malloc),
+        free_fn: Some(counting_free),
+    };
+    unsafe { init_hooks(&mut hooks) };
+}
+
+/// Run `f` and report the allocations it made on the current thread
+pub(crate) fn measure<R>(f: impl FnOnce() -> R) -> (R, AllocStats) {
+    let before = snapshot();
+    let ret = f();
+    let after = snapshot();
+
+    let stats = AllocStats {
+        rust_allocs: after.rust_allocs - before.rust_allocs,
+        rust_bytes: after.rust_bytes - before.rust_bytes,
+        rust_live_bytes: after.rust_live_bytes - before.rust_live_bytes,
+        c_allocs: after.c_allocs - before.c_allocs,
+        c_live: after.c_live - before.c_live,
+    };
+    (ret, stats)
+}
+
+/// Run `f` with the cJSON allocation after the first `succeed` ones failing
+pub(crate) fn fail_c_alloc_after<R>(succeed: usize, f: impl FnOnce() -> R) -> R {
+    C_ALLOCS_LEFT.with(|left| left.set(Some(succeed)));
+    let ret = f();
+    C_ALLOCS_LEFT.with(|left| left.set(None));
+    ret
+}
+
+fn snapshot() -> AllocStats {
+    AllocStats {
+        rust_allocs: RUST_ALLOCS.with(Cell::get),
+        rust_bytes: RUST_BYTES.with(Cell::get),
+        rust_live_bytes: RUST_LIVE_BYTES.with(Cell::get),
+        c_allocs: C_ALLOCS.with(Cell::get),
+        c_live: C_LIVE.with(Cell::get),
+    }
+}
@@ -25,20 +25,27 @@
 
 extern crate alloc;
 
-use alloc::ffi::CString;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::ffi::{CString, NulError};
 use alloc::string::String;
 use alloc::vec::Vec;
 use core::ffi::{CStr, c_char, c_int};
 use core::ptr;
 use core::fmt::Display;
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
 
 use crate::cjson_ffi::*;
 
+/// Largest integer magnitude up to which every integer is exact in an `f64` (2^53)
+const MAX_EXACT_INTEGER: u64 = 1 << 53;
+
 /// Result type for cJSON operations
 pub type CJsonResult<T> = Result<T, CJsonError>;
 
 /// Error types for cJSON operations
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum CJsonError {
     /// Failed to parse JSON
     ParseError,
@@ -54,6 +61,32 @@ pub enum CJsonError {
     AllocationError,
     /// Invalid operation
     InvalidOperation,
+    /// Number does not fit in the target type
+    NumberOutOfRange { value: f64, min: f64, max: f64 },
+    /// Array length does not match the expected fixed size
+    ArrayLengthMismatch { expected: usize, actual: usize },
+    /// Document nests deeper than allowed
+    DepthLimitExceeded { max: usize },
+    /// Document holds more items than allowed
+    NodeLimitExceeded { max: usize },
+    /// Object contains the same key twice
+    DuplicateKey { key: String },
+    /// Output does not fit in a fixed-capacity buffer
+    CapacityExceeded { capacity: usize },
+    /// String passed to C contains a NUL byte at `position`
+    InteriorNul { position: usize },
+    /// Input to parse is empty or only whitespace
+    EmptyInput,
+    /// Item has another type than the one expected
+    UnexpectedType { expected: &'static str, found: &'static str },
+    /// Input is longer than the accepted maximum
+    InputTooLarge { size: usize, max: usize },
+    /// Writing to an output sink failed
+    WriteFailed,
+    /// Item at `pointer` is missing or has another type than the one expected
+    SchemaError { pointer: String, expected: &'static str, found: &'static str },
+    /// Failed to parse JSON at byte `offset`, i.e. at 1-based `line` and `column`
+    ParseErrorAt { offset: usize, line: usize, column: usize },
 }
 
 impl Display for CJsonError {
@@ -66,10 +99,48 @@ impl Display for CJsonError {
             CJsonError::TypeError => write!(f, "Wrong type"),
             CJsonError::AllocationError => write!(f, "Memory allocation failed"),
             CJsonError::InvalidOperation => write!(f, "Invalid operation"),
+            CJsonError::NumberOutOfRange { value, min, max } => {
+                write!(f, "Number {} out of range [{}, {}]", value, min, max)
+            }
+            CJsonError::ArrayLengthMismatch { expected, actual } => {
+                write!(f, "Array length mismatch: expected {}, found {}", expected, actual)
+            }
+            CJsonError::DepthLimitExceeded { max } => write!(f, "Nesting depth exceeds {}", max),
+            CJsonError::NodeLimitExceeded { max } => write!(f, "Node count exceeds {}", max),
+            CJsonError::DuplicateKey { key } => write!(f, "Duplicate key \"{}\"", key),
+            CJsonError::CapacityExceeded { capacity } => write!(f, "Output exceeds capacity {}", capacity),
+            CJsonError::InteriorNul { position } => write!(f, "Interior NUL byte at position {}", position),
+            CJsonError::EmptyInput => write!(f, "Empty JSON input"),
+            CJsonError::UnexpectedType { expected, found } => {
+                write!(f, "Expected {}, found {}", expected, found)
+            }
+            CJsonError::InputTooLarge { size, max } => write!(f, "Input of {} bytes exceeds {}", size, max),
+            CJsonError::WriteFailed => write!(f, "Failed to write output"),
+            CJsonError::SchemaError { pointer, expected, found } => {
+                write!(f, "Schema error at \"{}\": expected {}, found {}", pointer, expected, found)
+            }
+            CJsonError::ParseErrorAt { offset, line, column } => {
+                write!(f, "Failed to parse JSON at line {}, column {} (byte {})", line, column, offset)
+            }
         }
     }
 }
 
+impl From<core::fmt::Error> for CJsonError {
+    fn from(_: core::fmt::Error) -> Self {
+        CJsonError::WriteFailed
+    }
+}
+
+impl From<NulError> for CJsonError {
+    fn from(err: NulError) -> Self {
+        CJsonError::InteriorNul { position: err.nul_position() }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CJsonError {}
+
 #[cfg(feature = "disable_panic")]
 impl From<osal_rs_serde::Error> for CJsonError {
     fn from(err: osal_rs_serde::Error) -> Self {
@@ -87,8 +158,46 @@ impl From<osal_rs_serde::Error> for CJsonError {
     }
 }
 
+/// Limits enforced by `CJson::parse_validated`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseLimits {
+    /// Maximum number of nested arrays/objects (a bare scalar has depth 0)
+    pub max_depth: usize,
+    /// Maximum number of items, root included
+    pub max_nodes: usize,
+    /// Reject objects that repeat a key
+    pub forbid_duplicate_keys: bool,
+}
+
+/// Type of a JSON item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CJsonType {
+    Null,
+    Bool,
+    Number,
+    String,
+    Array,
+    Object,
+    Raw,
+}
+
+impl CJsonType {
+    /// Name of the type, as returned by `CJson::type_name`
+    pub fn name(self) -> &'static str {
+        match self {
+            CJsonType::Null => "null",
+            CJsonType::Bool => "bool",
+            CJsonType::Number => "number",
+            CJsonType::String => "string",
+            CJsonType::Array => "array",
+            CJsonType::Object => "object",
+            CJsonType::Raw => "raw",
+        }
+    }
+}
+
 /// Safe wrapper for cJSON pointer
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct CJson {
     ptr: *mut cJSON,
 }
@@ -106,6 +215,22 @@ impl CJson {
         }
     }
 
+    /// Take ownership of a tree created by cJSON outside this crate
+    ///
+    /// This is the counterpart of `into_raw`. Returns `NullPointer` if `ptr` is null.
+    ///
+    /// # Safety
+    /// - `ptr` must point to a valid item allocated by the cJSON library this
+    ///   crate links against (with its hooks), e.g. by `cJSON_Parse`.
+    /// - It must be a root: not attached to an array or object, nor the target
+    ///   of a reference that outlives it.
+    /// - Ownership moves to the returned `CJson`: the caller must not free or
+    ///   keep using `ptr`, and the tree is released by `drop` (or handed back
+    ///   with `into_raw`).
+    pub unsafe fn from_owned_ptr(ptr: *mut cJSON) -> CJsonResult<Self> {
+        unsafe { Self::from_ptr(ptr) }
+    }
+
     /// Get the raw pointer (does not transfer ownership)
     pub fn as_ptr(&self) -> *const cJSON {
         self.ptr
@@ -116,6 +241,16 @@ impl CJson {
         self.ptr
     }
 
+    /// Create a second handle to the same node (no copy is made)
+    ///
+    /// # Safety
+    /// The returned handle does not own the node: it must never be dropped
+    /// while the original, or the parent it was added to, still owns it.
+    #[allow(dead_code)]
+    pub(crate) unsafe fn alias(&self) -> Self {
+        CJson { ptr: self.ptr }
+    }
+
     /// Consume the wrapper and return the raw pointer (transfers ownership)
     pub fn into_raw(self) -> *mut cJSON {
         let ptr = self.ptr;
@@ -135,22 +270,85 @@ impl CJson {
     // ========================
 
     /// Parse a JSON string
+    ///
+    /// The text is handed to cJSON by length, without an intermediate
+    /// NUL-terminated copy. A NUL byte in the text is rejected with
+    /// `InteriorNul` rather than silently ending a string.
     pub fn parse(json: &str) -> CJsonResult<Self> {
-        let c_str = CString::new(json).map_err(|_| CJsonError::InvalidUtf8)?;
-        let ptr = unsafe { cJSON_Parse(c_str.as_ptr()) };
-        unsafe { Self::from_ptr(ptr) }
+        Self::parse_with_length(json, json.len())
     }
 
-    /// Parse a JSON string with specified length
+    /// Parse the first `length` bytes of a JSON string (clamped to its length)
     pub fn parse_with_length(json: &str, length: usize) -> CJsonResult<Self> {
-        let c_str = CString::new(json).map_err(|_| CJsonError::InvalidUtf8)?;
-        let ptr = unsafe { cJSON_ParseWithLength(c_str.as_ptr(), length) };
+        let length = length.min(json.len());
+        let bytes = &json.as_bytes()[..length];
+        if let Some(position) = bytes.iter().position(|&b| b == 0) {
+            return Err(CJsonError::InteriorNul { position });
+        }
+        if is_blank(bytes) {
+            return Err(CJsonError::EmptyInput);
+        }
+        let ptr = unsafe { cJSON_ParseWithLength(json.as_ptr() as *const c_char, length) };
         unsafe { Self::from_ptr(ptr) }
     }
 
+    /// Parse the JSON value at the start of `data` and return it with the
+    /// number of bytes it took
+    ///
+    /// Anything after the value is left alone (e.g. a binary payload following a
+    /// JSON header). Whitespace right after the value counts as consumed.
+    pub fn parse_prefix(data: &[u8]) -> CJsonResult<(Self, usize)> {
+        let (ptr, offset) = Self::parse_prefix_raw(data)?;
+        let json = unsafe { Self::from_ptr(ptr) }?;
+        Ok((json, offset))
+    }
+
+    /// Same as `parse_prefix`, but invalid JSON fails with `ParseErrorAt`,
+    /// locating the offending byte by line and column (see `offset_to_line_col`)
+    pub fn parse_prefix_located(data: &[u8]) -> CJsonResult<(Self, usize)> {
+        let (ptr, offset) = Self::parse_prefix_raw(data)?;
+        if ptr.is_null() {
+            let head = match core::str::from_utf8(&data[..offset]) {
+                Ok(head) => head,
+                Err(e) => core::str::from_utf8(&data[..e.valid_up_to()]).unwrap_or_default(),
+            };
+            let (line, column) = offset_to_line_col(head, head.len());
+            return Err(CJsonError::ParseErrorAt { offset, line, column });
+        }
+        let json = unsafe { Self::from_ptr(ptr) }?;
+        Ok((json, offset))
+    }
+
+    /// Run cJSON on `data`; gives the root (null on failure) and the offset
+    /// where it stopped, which on failure is the offending byte
+    fn parse_prefix_raw(data: &[u8]) -> CJsonResult<(*mut cJSON, usize)> {
+        if is_blank(data) {
+            return Err(CJsonError::EmptyInput);
+        }
+        let start = data.as_ptr() as *const c_char;
+        let mut end: *const c_char = ptr::null();
+        let ptr = unsafe { cJSON_ParseWithLengthOpts(start, data.len(), &mut end, 0) };
+        let offset = if end.is_null() { 0 } else { end as usize - start as usize };
+        Ok((ptr, offset))
+    }
+
+    /// Parse a JSON string, refusing input longer than `max_bytes` before cJSON sees it
+    pub fn parse_bounded(json: &str, max_bytes: usize) -> CJsonResult<Self> {
+        if json.len() > max_bytes {
+            return Err(CJsonError::InputTooLarge { size: json.len(), max: max_bytes });
+        }
+        Self::parse(json)
+    }
+
     /// Parse a JSON string with options
+    ///
+    /// Goes through a `CString`, so interior NUL bytes are rejected with
+    /// `InteriorNul`.
     pub fn parse_with_opts(json: &str, require_null_terminated: bool) -> CJsonResult<Self> {
-        let c_str = CString::new(json).map_err(|_| CJsonError::InvalidUtf8)?;
+        if is_blank(json.as_bytes()) {
+            return Err(CJsonError::EmptyInput);
+        }
+        let c_str = CString::new(json)?;
         let ptr = unsafe {
             cJSON_ParseWithOpts(
                 c_str.as_ptr(),
@@ -161,30 +359,183 @@ impl CJson {
         unsafe { Self::from_ptr(ptr) }
     }
 
+    /// Parse hand-edited JSON that may contain `//` and `/* */` comments and
+    /// trailing commas before `]` or `}`
+    ///
+    /// The text is cleaned up in Rust first; string literals are left untouched.
+    pub fn parse_relaxed(json: &str) -> CJsonResult<Self> {
+        Self::parse(&strip_relaxed(json))
+    }
+
+    /// Parse a JSON string keeping every number exactly as written in the source
+    ///
+    /// Numbers are replaced by Raw nodes holding their original token (so `1.50`
+    /// prints as `1.50`). The number accessors still read them, but `is_number`
+    /// is false for these nodes.
+    pub fn parse_preserve_numbers(json: &str) -> CJsonResult<Self> {
+        let parsed = Self::parse(json)?;
+        let mut tokens = number_tokens(json).into_iter();
+        if parsed.is_number() {
+            let token = tokens.next().ok_or(CJsonError::ParseError);
+            parsed.drop();
+            return Self::create_raw(token?);
+        }
+        let mut next_token = |_| tokens.next().map(|t| Some(String::from(t))).ok_or(CJsonError::ParseError);
+        if let Err(e) = unsafe { numbers_to_raw_ptr(parsed.ptr, &mut next_token) } {
+            parsed.drop();
+            return Err(e);
+        }
+        Ok(parsed)
+    }
+
+    /// Read a whole document from `reader` and parse it
+    ///
+    /// Parse failures are reported as `InvalidData` wrapping the `CJsonError`.
+    #[cfg(feature = "std")]
+    pub fn from_reader<R: std::io::Read>(mut reader: R) -> std::io::Result<Self> {
+        let mut json = String::new();
+        reader.read_to_string(&mut json)?;
+        Self::parse(&json).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    /// Parse untrusted input and enforce `limits` on the resulting tree
+    ///
+    /// The tree is freed before returning any limit error.
+    pub fn parse_validated(json: &str, limits: ParseLimits) -> CJsonResult<Self> {
+        let parsed = Self::parse(json)?;
+        let mut nodes = 0;
+        if let Err(e) = unsafe { check_limits_ptr(parsed.ptr, 0, &limits, &mut nodes) } {
+            parsed.drop();
+            return Err(e);
+        }
+        Ok(parsed)
+    }
+
     // ========================
     // PRINTING FUNCTIONS
     // ========================
 
     /// Print JSON to a formatted string
     pub fn print(&self) -> CJsonResult<String> {
-        let c_str = unsafe { cJSON_Print(self.ptr) };
-        if c_str.is_null() {
-            return Err(CJsonError::AllocationError);
+        let c_str = unsafe { COwnedStr::new(cJSON_Print(self.ptr)) }
+            .ok_or(CJsonError::AllocationError)?;
+        Ok(c_str.to_string_lossy())
+    }
+
+    /// Print only the sub-tree at the RFC6901 `pointer` (case sensitive)
+    ///
+    /// Returns `NotFound` if the pointer does not resolve.
+    pub fn print_at(&self, pointer: &str, formatted: bool) -> CJsonResult<String> {
+        let item = crate::cjson_utils::JsonPointer::get_case_sensitive(self, pointer)?;
+        if formatted {
+            item.print()
+        } else {
+            item.print_unformatted()
         }
-        let rust_str = unsafe { CStr::from_ptr(c_str).to_string_lossy().into_owned() };
-        unsafe { cJSON_free(c_str as *mut core::ffi::c_void) };
-        Ok(rust_str)
+    }
+
+    /// Print JSON unformatted with every number in its shortest round-trip form
+    ///
+    /// Parsing the output gives back bit-identical `f64` values, where the
+    /// regular print may lose the last digits (e.g. of `0.1 + 0.2`).
+    pub fn print_full_precision(&self) -> CJsonResult<String> {
+        if self.is_number() {
+            let value = self.get_number_value()?;
+            return match round_trip_text(value) {
+                Some(text) => Ok(text),
+                None => self.print_unformatted(),
+            };
+        }
+        // Work on a copy so the tree itself keeps native numbers
+        let shadow = self.duplicate(true)?;
+        let ret = unsafe { numbers_to_raw_ptr(shadow.ptr, &mut |value| Ok(round_trip_text(value))) }
+            .and_then(|_| shadow.print_unformatted());
+        shadow.drop();
+        ret
+    }
+
+    /// Print JSON unformatted into `writer`
+    #[cfg(feature = "std")]
+    pub fn to_writer<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<()> {
+        let json = self
+            .print_unformatted()
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::OutOfMemory, e))?;
+        writer.write_all(json.as_bytes())
     }
 
     /// Print JSON to an unformatted string
     pub fn print_unformatted(&self) -> CJsonResult<String> {
-        let c_str = unsafe { cJSON_PrintUnformatted(self.ptr) };
-        if c_str.is_null() {
-            return Err(CJsonError::AllocationError);
+        let c_str = unsafe { COwnedStr::new(cJSON_PrintUnformatted(self.ptr)) }
+            .ok_or(CJsonError::AllocationError)?;
+        Ok(c_str.to_string_lossy())
+    }
+
+    /// Print the tree and free it, in one move
+    ///
+    /// The tree is freed whether printing succeeds or not.
+    pub fn into_string(self, formatted: bool) -> CJsonResult<String> {
+        let ret = if formatted { self.print() } else { self.print_unformatted() };
+        self.drop();
+        ret
+    }
+
+    /// Get the exact number of bytes the printed JSON would take (without the NUL terminator)
+    ///
+    /// Useful to size a buffer before a preallocated print.
+    pub fn printed_size(&self, formatted: bool) -> CJsonResult<usize> {
+        let c_str = unsafe {
+            COwnedStr::new(if formatted {
+                cJSON_Print(self.ptr)
+            } else {
+                cJSON_PrintUnformatted(self.ptr)
+            })
+        }
+        .ok_or(CJsonError::AllocationError)?;
+        Ok(c_str.as_c_str().to_bytes().len())
+    }
+
+    /// Describe the tree for logs, bounded in size
+    ///
+    /// JSON-like, but containers show at most `max_children` children followed
+    /// by `… (K more)`, and strings (values and keys) are cut after `max_str`
+    /// characters with a trailing `…`. `Debug` uses this with small limits.
+    pub fn debug_summary(&self, max_children: usize, max_str: usize) -> String {
+        let mut out = String::new();
+        unsafe { debug_summary_ptr(self.ptr, max_children, max_str, &mut out) };
+        out
+    }
+
+    /// Print JSON into a fixed-capacity `heapless::String` without touching the heap
+    ///
+    /// Returns `CapacityExceeded` if the output is longer than `N` bytes.
+    #[cfg(feature = "heapless")]
+    pub fn print_heapless<const N: usize>(&self, formatted: bool) -> CJsonResult<heapless::String<N>> {
+        // cJSON needs room for the NUL terminator and may overestimate the
+        // space a number takes by a few bytes, so print into N + 8
+        #[repr(C)]
+        struct Buffer<const N: usize> {
+            data: [u8; N],
+            slack: [u8; 8],
         }
-        let rust_str = unsafe { CStr::from_ptr(c_str).to_string_lossy().into_owned() };
-        unsafe { cJSON_free(c_str as *mut core::ffi::c_void) };
-        Ok(rust_str)
+
+        let mut buffer = Buffer::<N> { data: [0; N], slack: [0; 8] };
+        let length = c_int::try_from(N + 8).map_err(|_| CJsonError::CapacityExceeded { capacity: N })?;
+        let ok = unsafe {
+            cJSON_PrintPreallocated(
+                self.ptr,
+                &mut buffer as *mut Buffer<N> as *mut c_char,
+                length,
+                if formatted { 1 } else { 0 },
+            )
+        };
+        if ok == 0 {
+            return Err(CJsonError::CapacityExceeded { capacity: N });
+        }
+        let printed = unsafe { CStr::from_ptr(&buffer as *const Buffer<N> as *const c_char) };
+        let text = printed.to_str().map_err(|_| CJsonError::InvalidUtf8)?;
+        let mut out = heapless::String::new();
+        out.push_str(text).map_err(|_| CJsonError::CapacityExceeded { capacity: N })?;
+        Ok(out)
     }
 
     // ========================
@@ -241,6 +592,23 @@ impl CJson {
         unsafe { cJSON_IsRaw(self.ptr) != 0 }
     }
 
+    /// Name of the item's type ("null", "bool", "number", "string", "array", "object", "raw" or "invalid")
+    pub fn type_name(&self) -> &'static str {
+        unsafe { type_name_ptr(self.ptr) }
+    }
+
+    /// Whether the item is a reference (`cJSON_IsReference`): its children or
+    /// string belong to another item and are not freed with it
+    pub fn is_reference(&self) -> bool {
+        (unsafe { raw_type_bits(self.ptr) } & cJSON_IsReference) != 0
+    }
+
+    /// Whether the item's key is a constant string (`cJSON_StringIsConst`),
+    /// stored by pointer and not freed with it
+    pub fn is_const_string(&self) -> bool {
+        (unsafe { raw_type_bits(self.ptr) } & cJSON_StringIsConst) != 0
+    }
+
     // ========================
     // VALUE RETRIEVAL FUNCTIONS
     // ========================
@@ -257,28 +625,76 @@ impl CJson {
         Ok(unsafe { CStr::from_ptr(c_str).to_string_lossy().into_owned() })
     }
 
-    /// Get number value as f64
-    pub fn get_number_value(&self) -> CJsonResult<f64> {
-        if !self.is_number() {
+    /// Borrow the string value as a `CStr` (valid while `self` is not modified or freed)
+    pub fn as_cstr(&self) -> CJsonResult<&CStr> {
+        if !self.is_string() {
             return Err(CJsonError::TypeError);
         }
-        Ok(unsafe { cJSON_GetNumberValue(self.ptr) })
+        let c_str = unsafe { cJSON_GetStringValue(self.ptr) };
+        if c_str.is_null() {
+            return Err(CJsonError::NullPointer);
+        }
+        Ok(unsafe { CStr::from_ptr(c_str) })
     }
 
-    /// Get number value as i32
+    /// Get number value as f64 (also reads Raw nodes holding numeric text)
+    pub fn get_number_value(&self) -> CJsonResult<f64> {
+        unsafe { number_value_ptr(self.ptr) }
+    }
+
+    /// Get number value as i64 (fractions are truncated)
+    pub fn get_i64(&self) -> CJsonResult<i64> {
+        unsafe { i64_value_ptr(self.ptr) }
+    }
+
+    /// Get number value as u64 (fractions are truncated)
+    pub fn get_u64(&self) -> CJsonResult<u64> {
+        unsafe { u64_value_ptr(self.ptr) }
+    }
+
+    /// Get number value as i32 (fractions are truncated)
+    ///
+    /// cJSON saturates its `int` copy of the number, so values outside the
+    /// `i32` range return `NumberOutOfRange`; use `get_i64` for those.
     pub fn get_int_value(&self) -> CJsonResult<i32> {
-        if !self.is_number() {
-            return Err(CJsonError::TypeError);
-        }
-        Ok(unsafe { (*self.ptr).valueint })
+        unsafe { int_value_ptr(self.ptr) }
     }
 
     /// Get boolean value
     pub fn get_bool_value(&self) -> CJsonResult<bool> {
+        unsafe { bool_value_ptr(self.ptr) }
+    }
+
+    /// Get boolean value, also accepting numbers (non-zero is true)
+    pub fn as_bool_lenient(&self) -> CJsonResult<bool> {
+        if self.is_bool() {
+            Ok(self.is_true())
+        } else if self.is_number() {
+            Ok(self.get_number_value()? != 0.0)
+        } else {
+            Err(CJsonError::TypeError)
+        }
+    }
+
+    /// Render a scalar as plain text: strings without quotes, numbers by
+    /// value, bools as "true"/"false", null as "null" and Raw nodes verbatim.
+    /// Arrays and objects return `TypeError`; use `print` for those.
+    pub fn to_display_string(&self) -> CJsonResult<String> {
+        unsafe { display_string_ptr(self.ptr) }
+    }
+
+    // ========================
+    // VALUE MODIFICATION FUNCTIONS
+    // ========================
+
+    /// Set the value of a boolean item
+    pub fn set_bool_value(&mut self, value: bool) -> CJsonResult<()> {
         if !self.is_bool() {
             return Err(CJsonError::TypeError);
         }
-        Ok(self.is_true())
+        let item = unsafe { &mut *self.ptr };
+        item.type_ = (item.type_ & !(cJSON_False | cJSON_True)) | if value { cJSON_True } else { cJSON_False };
+        Ok(())
     }
 
     // ========================
@@ -293,6 +709,72 @@ impl CJson {
         Ok(unsafe { cJSON_GetArraySize(self.ptr) as usize })
     }
 
+    /// Whether an array or object has no children
+    pub fn is_empty(&self) -> CJsonResult<bool> {
+        if !self.is_array() && !self.is_object() {
+            return Err(CJsonError::TypeError);
+        }
+        Ok(unsafe { (*self.ptr).child }.is_null())
+    }
+
+    /// Lazily iterate over the array items (borrowed references)
+    pub fn array_iter(&self) -> CJsonResult<ArrayIter<'_>> {
+        if !self.is_array() {
+            return Err(CJsonError::TypeError);
+        }
+        Ok(ArrayIter {
+            next: unsafe { (*self.ptr).child },
+            _marker: PhantomData,
+        })
+    }
+
+    /// Index the object's members by key for repeated lookups
+    ///
+    /// Building scans the members once; `ObjectIndex::get` is then O(log n)
+    /// instead of the O(n) scan of `get_object_item`. Keys are matched case
+    /// sensitively, the first of duplicate keys wins and keys that are not
+    /// valid UTF-8 are left out. Anything but an object gives an empty index.
+    ///
+    /// The index borrows `self`, but mutating the tree through an alias or C
+    /// code while it is alive leaves it stale. `drop` takes `&self`, so the
+    /// borrow cannot prevent it either: once `self` is dropped the index points
+    /// at freed nodes and must not be used.
+    pub fn build_index(&self) -> ObjectIndex<'_> {
+        let mut members = BTreeMap::new();
+        if self.is_object() {
+            let mut child = unsafe { (*self.ptr).child };
+            while !child.is_null() {
+                let key = unsafe { (*child).string };
+                if !key.is_null()
+                    && let Ok(key) = unsafe { CStr::from_ptr(key) }.to_str()
+                {
+                    members.entry(key).or_insert(child);
+                }
+                child = unsafe { (*child).next };
+            }
+        }
+        ObjectIndex { members }
+    }
+
+    /// Lazily iterate over the array items with in-place value mutation
+    ///
+    /// The array stays mutably borrowed, so items cannot be inserted or removed meanwhile.
+    pub fn array_iter_mut(&mut self) -> CJsonResult<ArrayIterMut<'_>> {
+        if !self.is_array() {
+            return Err(CJsonError::TypeError);
+        }
+        Ok(ArrayIterMut {
+            next: unsafe { (*self.ptr).child },
+            _marker: PhantomData,
+        })
+    }
+
+    /// Sum a numeric array without collecting it; fails on a non-number element
+    pub fn array_sum_f64(&self) -> CJsonResult<f64> {
+        self.array_iter()?
+            .try_fold(0.0, |acc, item| Ok(acc + item.get_number_value()?))
+    }
+
     /// Get array item by index (borrowed reference)
     pub fn get_array_item(&self, index: usize) -> CJsonResult<CJsonRef> {
         if !self.is_array() {
@@ -302,16 +784,38 @@ impl CJson {
         unsafe { CJsonRef::from_ptr(ptr) }.map_err(|_| CJsonError::NotFound)
     }
 
+    /// Get array item by index, negative indices counting from the end (`-1` is the last)
+    pub fn get_array_item_rel(&self, index: isize) -> CJsonResult<CJsonRef> {
+        if index >= 0 {
+            return self.get_array_item(index as usize);
+        }
+        let size = self.get_array_size()?;
+        let back = index.unsigned_abs();
+        if back > size {
+            return Err(CJsonError::NotFound);
+        }
+        self.get_array_item(size - back)
+    }
+
     // ========================
     // OBJECT FUNCTIONS
     // ========================
 
+    /// Get the number of object members
+    pub fn object_size(&self) -> CJsonResult<usize> {
+        if !self.is_object() {
+            return Err(CJsonError::TypeError);
+        }
+        // cJSON_GetArraySize counts the children of any container
+        Ok(unsafe { cJSON_GetArraySize(self.ptr) as usize })
+    }
+
     /// Get object item by key (borrowed reference)
     pub fn get_object_item(&self, key: &str) -> CJsonResult<CJsonRef> {
         if !self.is_object() {
             return Err(CJsonError::TypeError);
         }
-        let c_key = CString::new(key).map_err(|_| CJsonError::InvalidUtf8)?;
+        let c_key = CString::new(key)?;
         let ptr = unsafe { cJSON_GetObjectItem(self.ptr, c_key.as_ptr()) };
         unsafe { CJsonRef::from_ptr(ptr) }.map_err(|_| CJsonError::NotFound)
     }
@@ -321,7 +825,7 @@ impl CJson {
         if !self.is_object() {
             return Err(CJsonError::TypeError);
         }
-        let c_key = CString::new(key).map_err(|_| CJsonError::InvalidUtf8)?;
+        let c_key = CString::new(key)?;
         let ptr = unsafe { cJSON_GetObjectItemCaseSensitive(self.ptr, c_key.as_ptr()) };
         unsafe { CJsonRef::from_ptr(ptr) }.map_err(|_| CJsonError::NotFound)
     }
@@ -337,6 +841,64 @@ impl CJson {
         unsafe { cJSON_HasObjectItem(self.ptr, c_key.as_ptr()) != 0 }
     }
 
+    /// Check if object has every key in `keys`
+    pub fn has_all_keys(&self, keys: &[&str], case_sensitive: bool) -> bool {
+        keys.iter().all(|key| {
+            if case_sensitive {
+                self.get_object_item_case_sensitive(key).is_ok()
+            } else {
+                self.has_object_item(key)
+            }
+        })
+    }
+
+    /// List the keys in `keys` that the object lacks (case sensitive)
+    ///
+    /// A non-object is missing every key.
+    pub fn missing_keys(&self, keys: &[&str]) -> Vec<String> {
+        keys.iter()
+            .filter(|key| self.get_object_item_case_sensitive(key).is_err())
+            .map(|key| String::from(*key))
+            .collect()
+    }
+
+    /// Visit each object member in order, passing the borrowed key and value.
+    ///
+    /// Walks the children once without allocating; stops at the first error
+    /// returned by `f`.
+    pub fn visit_object<F>(&self, mut f: F) -> CJsonResult<()>
+    where
+        F: FnMut(&str, &CJsonRef) -> CJsonResult<()>,
+    {
+        if !self.is_object() {
+            return Err(CJsonError::TypeError);
+        }
+        unsafe { visit_object_ptr(self.ptr, &mut f) }
+    }
+
+    /// Visit every node in pre-order with its RFC6901 JSON Pointer (`""` for the root)
+    ///
+    /// Stops at the first error returned by `f`.
+    pub fn walk<F>(&self, mut f: F) -> CJsonResult<()>
+    where
+        F: FnMut(&str, &CJsonRef) -> CJsonResult<()>,
+    {
+        let mut path = String::new();
+        unsafe { walk_ptr(self.ptr, &mut path, &mut f) }
+    }
+
+    /// Collect the object's key/value pairs in insertion order
+    ///
+    /// The returned references borrow from `self` and must not outlive it.
+    pub fn object_entries(&self) -> CJsonResult<Vec<(String, CJsonRef)>> {
+        let mut entries = Vec::new();
+        self.visit_object(|key, value| {
+            entries.push((String::from(key), CJsonRef { ptr: value.ptr }));
+            Ok(())
+        })?;
+        Ok(entries)
+    }
+
     // ========================
     // CREATION FUNCTIONS
     // ========================
@@ -371,13 +933,56 @@ impl CJson {
         unsafe { Self::from_ptr(ptr) }
     }
 
+    /// Create an integer number, always printed as plain digits
+    ///
+    /// Beyond ±2^53 an `f64` cannot hold every integer and cJSON would print an
+    /// exponent, so such values are stored as a Raw node with their exact digits;
+    /// `get_i64` reads them back unchanged.
+    pub fn create_i64(value: i64) -> CJsonResult<Self> {
+        if value.unsigned_abs() <= MAX_EXACT_INTEGER {
+            Self::create_number(value as f64)
+        } else {
+            Self::create_raw(&format!("{}", value))
+        }
+    }
+
+    /// Create an unsigned integer number, see `create_i64`
+    pub fn create_u64(value: u64) -> CJsonResult<Self> {
+        if value <= MAX_EXACT_INTEGER {
+            Self::create_number(value as f64)
+        } else {
+            Self::create_raw(&format!("{}", value))
+        }
+    }
+
+    /// Create a number printed with exactly `decimals` fractional digits
+    ///
+    /// The value is formatted in Rust and stored as a Raw node; the number
+    /// accessors still read it. Non-finite values are rejected.
+    pub fn create_number_formatted(value: f64, decimals: usize) -> CJsonResult<Self> {
+        if !value.is_finite() {
+            return Err(CJsonError::InvalidOperation);
+        }
+        let text = format!("{:.*}", decimals, value);
+        let c_str = CString::new(text)?;
+        let ptr = unsafe { cJSON_CreateRaw(c_str.as_ptr()) };
+        unsafe { Self::from_ptr(ptr) }
+    }
+
     /// Create a string value
     pub fn create_string(value: &str) -> CJsonResult<Self> {
-        let c_str = CString::new(value).map_err(|_| CJsonError::InvalidUtf8)?;
+        let c_str = CString::new(value)?;
         let ptr = unsafe { cJSON_CreateString(c_str.as_ptr()) };
         unsafe { Self::from_ptr(ptr) }
     }
 
+    /// Create a raw value, printed verbatim without validation
+    pub fn create_raw(value: &str) -> CJsonResult<Self> {
+        let c_str = CString::new(value)?;
+        let ptr = unsafe { cJSON_CreateRaw(c_str.as_ptr()) };
+        unsafe { Self::from_ptr(ptr) }
+    }
+
     /// Create an array
     pub fn create_array() -> CJsonResult<Self> {
         let ptr = unsafe { cJSON_CreateArray() };
@@ -390,6 +995,35 @@ impl CJson {
         unsafe { Self::from_ptr(ptr) }
     }
 
+    /// Create an object holding `pairs` in order
+    ///
+    /// The values are moved into the object: on success the caller must not
+    /// `drop` them anymore. A repeated key fails with `DuplicateKey` before
+    /// anything is built, and a failed key copy with `AllocationError` after
+    /// taking the values back out: on error every value is owned by the caller.
+    pub fn object_from_pairs(pairs: &[(&str, CJson)]) -> CJsonResult<Self> {
+        let mut keys = BTreeSet::new();
+        for (key, _) in pairs {
+            if !keys.insert(*key) {
+                return Err(CJsonError::DuplicateKey { key: String::from(*key) });
+            }
+        }
+        // Keys are converted up front so a NUL in one cannot leave the values half moved
+        let c_keys = pairs.iter().map(|(key, _)| CString::new(*key)).collect::<Result<Vec<_>, _>>()?;
+
+        let object = Self::create_object()?;
+        for (added, ((_, value), c_key)) in pairs.iter().zip(&c_keys).enumerate() {
+            if unsafe { cJSON_AddItemToObject(object.ptr, c_key.as_ptr(), value.ptr) } == 0 {
+                for (_, value) in &pairs[..added] {
+                    unsafe { cJSON_DetachItemViaPointer(object.ptr, value.ptr) };
+                }
+                object.drop();
+                return Err(CJsonError::AllocationError);
+            }
+        }
+        Ok(object)
+    }
+
     /// Create an integer array
     pub fn create_int_array(values: &[i32]) -> CJsonResult<Self> {
         let ptr = unsafe { cJSON_CreateIntArray(values.as_ptr(), values.len() as c_int) };
@@ -407,8 +1041,7 @@ impl CJson {
         let c_strings: Vec<CString> = values
             .iter()
             .map(|s| CString::new(*s))
-            .collect::<Result<_, _>>()
-            .map_err(|_| CJsonError::InvalidUtf8)?;
+            .collect::<Result<_, _>>()?;
         
         let c_ptrs: Vec<*const c_char> = c_strings.iter().map(|s| s.as_ptr()).collect();
         
@@ -416,6 +1049,37 @@ impl CJson {
         unsafe { Self::from_ptr(ptr) }
     }
 
+    /// Create a number array from an iterator
+    pub fn number_array_from_iter<I>(iter: I) -> CJsonResult<Self>
+    where
+        I: IntoIterator<Item = f64>,
+    {
+        let mut array = Self::create_array()?;
+        for value in iter {
+            if let Err(e) = Self::create_number(value).and_then(|item| array.add_item_to_array(item)) {
+                array.drop();
+                return Err(e);
+            }
+        }
+        Ok(array)
+    }
+
+    /// Create a string array from an iterator
+    pub fn string_array_from_iter<I>(iter: I) -> CJsonResult<Self>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut array = Self::create_array()?;
+        for value in iter {
+            if let Err(e) = Self::create_string(value.as_ref()).and_then(|item| array.add_item_to_array(item)) {
+                array.drop();
+                return Err(e);
+            }
+        }
+        Ok(array)
+    }
+
     // ========================
     // ARRAY MANIPULATION FUNCTIONS
     // ========================
@@ -433,6 +1097,16 @@ impl CJson {
         }
     }
 
+    /// Append an already serialized JSON fragment to the array
+    ///
+    /// The fragment is not parsed: it is printed verbatim, so it must be valid JSON.
+    pub fn add_raw_to_array(&mut self, raw: &str) -> CJsonResult<()> {
+        if !self.is_array() {
+            return Err(CJsonError::TypeError);
+        }
+        self.add_item_to_array(Self::create_raw(raw)?)
+    }
+
     /// Delete item from array by index
     pub fn delete_item_from_array(&mut self, index: usize) -> CJsonResult<()> {
         if !self.is_array() {
@@ -451,6 +1125,54 @@ impl CJson {
         unsafe { Self::from_ptr(ptr) }
     }
 
+    /// Remove the item at `index` and return it (`NotFound` if out of range)
+    pub fn array_remove(&mut self, index: usize) -> CJsonResult<CJson> {
+        if index >= self.get_array_size()? {
+            return Err(CJsonError::NotFound);
+        }
+        self.detach_item_from_array(index)
+    }
+
+    /// New array with deep copies of the items of `self`, then those of `other`
+    /// not structurally equal (see `compare`) to an item already added
+    ///
+    /// Items of `self` are all kept. Each item of `other` is compared against
+    /// everything added so far, so the cost is O(n·m).
+    pub fn array_union(&self, other: &CJson, case_sensitive: bool) -> CJsonResult<CJson> {
+        if !self.is_array() || !other.is_array() {
+            return Err(CJsonError::TypeError);
+        }
+        let union = self.duplicate(true)?;
+        let mut item = unsafe { (*other.ptr).child };
+        while !item.is_null() {
+            let mut present = unsafe { (*union.ptr).child };
+            while !present.is_null() && !unsafe { structurally_eq_ptr(present, item, case_sensitive) } {
+                present = unsafe { (*present).next };
+            }
+            if present.is_null() {
+                let copy = unsafe { cJSON_Duplicate(item, 1) };
+                if copy.is_null() || unsafe { cJSON_AddItemToArray(union.ptr, copy) } == 0 {
+                    unsafe { cJSON_Delete(copy) };
+                    union.drop();
+                    return Err(CJsonError::AllocationError);
+                }
+            }
+            item = unsafe { (*item).next };
+        }
+        Ok(union)
+    }
+
+    /// Detach every item, leaving the array empty, and return them in order
+    ///
+    /// The caller must `drop` each returned item.
+    pub fn drain_array(&mut self) -> CJsonResult<Vec<CJson>> {
+        let mut items = Vec::with_capacity(self.get_array_size()?);
+        while !unsafe { (*self.ptr).child }.is_null() {
+            items.push(self.detach_item_from_array(0)?);
+        }
+        Ok(items)
+    }
+
     // ========================
     // OBJECT MANIPULATION FUNCTIONS
     // ========================
@@ -460,7 +1182,23 @@ impl CJson {
         if !self.is_object() {
             return Err(CJsonError::TypeError);
         }
-        let c_key = CString::new(key).map_err(|_| CJsonError::InvalidUtf8)?;
+        let c_key = CString::new(key)?;
+        let result = unsafe { cJSON_AddItemToObject(self.ptr, c_key.as_ptr(), item.into_raw()) };
+        if result != 0 {
+            Ok(())
+        } else {
+            Err(CJsonError::InvalidOperation)
+        }
+    }
+
+    /// Add item to object under a key given as raw bytes
+    ///
+    /// The key is copied as is, without assuming UTF-8; it must not contain NUL.
+    pub fn add_item_to_object_bytes(&mut self, key: &[u8], item: CJson) -> CJsonResult<()> {
+        if !self.is_object() {
+            return Err(CJsonError::TypeError);
+        }
+        let c_key = CString::new(key)?;
         let result = unsafe { cJSON_AddItemToObject(self.ptr, c_key.as_ptr(), item.into_raw()) };
         if result != 0 {
             Ok(())
@@ -469,12 +1207,29 @@ impl CJson {
         }
     }
 
+    /// Add item to object using a constant key
+    ///
+    /// The key is stored by pointer (flagged `cJSON_StringIsConst`) instead of
+    /// being copied, saving one allocation per member. It is never freed by
+    /// cJSON, hence the `'static` bound: it must outlive the object.
+    pub fn add_item_to_object_cs(&mut self, key: &'static CStr, item: CJson) -> CJsonResult<()> {
+        if !self.is_object() {
+            return Err(CJsonError::TypeError);
+        }
+        let result = unsafe { cJSON_AddItemToObjectCS(self.ptr, key.as_ptr(), item.into_raw()) };
+        if result != 0 {
+            Ok(())
+        } else {
+            Err(CJsonError::InvalidOperation)
+        }
+    }
+
     /// Add null to object
     pub fn add_null_to_object(&mut self, key: &str) -> CJsonResult<()> {
         if !self.is_object() {
             return Err(CJsonError::TypeError);
         }
-        let c_key = CString::new(key).map_err(|_| CJsonError::InvalidUtf8)?;
+        let c_key = CString::new(key)?;
         let ptr = unsafe { cJSON_AddNullToObject(self.ptr, c_key.as_ptr()) };
         if ptr.is_null() {
             Err(CJsonError::AllocationError)
@@ -488,7 +1243,7 @@ impl CJson {
         if !self.is_object() {
             return Err(CJsonError::TypeError);
         }
-        let c_key = CString::new(key).map_err(|_| CJsonError::InvalidUtf8)?;
+        let c_key = CString::new(key)?;
         let ptr = unsafe { cJSON_AddTrueToObject(self.ptr, c_key.as_ptr()) };
         if ptr.is_null() {
             Err(CJsonError::AllocationError)
@@ -502,7 +1257,7 @@ impl CJson {
         if !self.is_object() {
             return Err(CJsonError::TypeError);
         }
-        let c_key = CString::new(key).map_err(|_| CJsonError::InvalidUtf8)?;
+        let c_key = CString::new(key)?;
         let ptr = unsafe { cJSON_AddFalseToObject(self.ptr, c_key.as_ptr()) };
         if ptr.is_null() {
             Err(CJsonError::AllocationError)
@@ -516,7 +1271,7 @@ impl CJson {
         if !self.is_object() {
             return Err(CJsonError::TypeError);
         }
-        let c_key = CString::new(key).map_err(|_| CJsonError::InvalidUtf8)?;
+        let c_key = CString::new(key)?;
         let ptr = unsafe {
             cJSON_AddBoolToObject(self.ptr, c_key.as_ptr(), if value { 1 } else { 0 })
         };
@@ -532,7 +1287,7 @@ impl CJson {
         if !self.is_object() {
             return Err(CJsonError::TypeError);
         }
-        let c_key = CString::new(key).map_err(|_| CJsonError::InvalidUtf8)?;
+        let c_key = CString::new(key)?;
         let ptr = unsafe { cJSON_AddNumberToObject(self.ptr, c_key.as_ptr(), value) };
         if ptr.is_null() {
             Err(CJsonError::AllocationError)
@@ -546,8 +1301,8 @@ impl CJson {
         if !self.is_object() {
             return Err(CJsonError::TypeError);
         }
-        let c_key = CString::new(key).map_err(|_| CJsonError::InvalidUtf8)?;
-        let c_value = CString::new(value).map_err(|_| CJsonError::InvalidUtf8)?;
+        let c_key = CString::new(key)?;
+        let c_value = CString::new(value)?;
         let ptr = unsafe { cJSON_AddStringToObject(self.ptr, c_key.as_ptr(), c_value.as_ptr()) };
         if ptr.is_null() {
             Err(CJsonError::AllocationError)
@@ -561,21 +1316,61 @@ impl CJson {
         if !self.is_object() {
             return Err(CJsonError::TypeError);
         }
-        let c_key = CString::new(key).map_err(|_| CJsonError::InvalidUtf8)?;
+        let c_key = CString::new(key)?;
         unsafe { cJSON_DeleteItemFromObject(self.ptr, c_key.as_ptr()) };
         Ok(())
     }
 
+    /// Set `key` to `item`, replacing an existing member in place (keeping its
+    /// position) or appending a new one; keys are case sensitive
+    pub fn set_item_in_object(&mut self, key: &str, item: CJson) -> CJsonResult<()> {
+        if !self.is_object() {
+            return Err(CJsonError::TypeError);
+        }
+        let c_key = CString::new(key)?;
+        let result = unsafe {
+            if cJSON_GetObjectItemCaseSensitive(self.ptr, c_key.as_ptr()).is_null() {
+                cJSON_AddItemToObject(self.ptr, c_key.as_ptr(), item.into_raw())
+            } else {
+                cJSON_ReplaceItemInObjectCaseSensitive(self.ptr, c_key.as_ptr(), item.into_raw())
+            }
+        };
+        if result != 0 {
+            Ok(())
+        } else {
+            Err(CJsonError::InvalidOperation)
+        }
+    }
+
     /// Detach item from object by key
     pub fn detach_item_from_object(&mut self, key: &str) -> CJsonResult<CJson> {
         if !self.is_object() {
             return Err(CJsonError::TypeError);
         }
-        let c_key = CString::new(key).map_err(|_| CJsonError::InvalidUtf8)?;
+        let c_key = CString::new(key)?;
         let ptr = unsafe { cJSON_DetachItemFromObject(self.ptr, c_key.as_ptr()) };
         unsafe { Self::from_ptr(ptr) }
     }
 
+    /// Detach a specific direct child of this array/object (`NotFound` if it is not one)
+    ///
+    /// Unlike `detach_item_from_object`, this targets the exact node, so any one
+    /// of several members sharing a key can be removed.
+    pub fn detach_child(&mut self, child: CJsonRef) -> CJsonResult<CJson> {
+        if !self.is_object() && !self.is_array() {
+            return Err(CJsonError::TypeError);
+        }
+        let mut current = unsafe { (*self.ptr).child };
+        while !current.is_null() && current != child.ptr {
+            current = unsafe { (*current).next };
+        }
+        if current.is_null() {
+            return Err(CJsonError::NotFound);
+        }
+        let ptr = unsafe { cJSON_DetachItemViaPointer(self.ptr, child.ptr) };
+        unsafe { Self::from_ptr(ptr) }
+    }
+
     // ========================
     // UTILITY FUNCTIONS
     // ========================
@@ -586,12 +1381,43 @@ impl CJson {
         unsafe { Self::from_ptr(ptr) }
     }
 
+    /// Check that every string value and object key in the tree is valid UTF-8
+    ///
+    /// cJSON stores strings as raw bytes, so a tree built from foreign data can
+    /// hold sequences that `get_string_value` would silently lossy-convert.
+    pub fn validate_utf8(&self) -> CJsonResult<()> {
+        unsafe { validate_utf8_ptr(self.ptr) }
+    }
+
     /// Compare two JSON items
     pub fn compare(&self, other: &CJson, case_sensitive: bool) -> bool {
         unsafe {
             cJSON_Compare(self.ptr, other.ptr, if case_sensitive { 1 } else { 0 }) != 0
         }
     }
+
+    /// Compare two JSON items reading numbers by value, whether stored natively
+    /// or as Raw numeric text (so `1` equals `1.0`); keys are case sensitive
+    pub fn compare_numeric_normalized(&self, other: &CJson) -> bool {
+        unsafe { compare_normalized_ptr(self.ptr, other.ptr) }
+    }
+
+    /// Compare two JSON items treating every array as a multiset
+    ///
+    /// Object members are matched by key and compared recursively, scalars use
+    /// `compare`. Matching array elements is O(n²) per array.
+    pub fn compare_unordered(&self, other: &CJson, case_sensitive: bool) -> bool {
+        unsafe { compare_unordered_ptr(self.ptr, other.ptr, case_sensitive) }
+    }
+
+    /// Same result as `compare`, computed by walking both trees in Rust
+    ///
+    /// Stops at the first difference, as `cJSON_Compare` does. The difference
+    /// is for equal objects: members already matched from `self` are not
+    /// compared a second time from `other`, so the walk is done once, not twice.
+    pub fn structurally_eq(&self, other: &CJson, case_sensitive: bool) -> bool {
+        unsafe { structurally_eq_ptr(self.ptr, other.ptr, case_sensitive) }
+    }
 }
 
 // impl Drop for CJson {
@@ -603,60 +1429,257 @@ impl CJson {
 //     }
 // }
 
-/// Borrowed reference to a cJSON item (does not own the pointer)
-pub struct CJsonRef {
-    ptr: *mut cJSON,
-}
+/// C string allocated by cJSON, released with `cJSON_free` when dropped
+pub(crate) struct COwnedStr(*mut c_char);
 
-impl CJsonRef {
-    /// Create a new CJsonRef from a raw pointer (does not take ownership)
-    /// 
+impl COwnedStr {
+    /// Take ownership of a string returned by cJSON; `None` if it is null
+    ///
     /// # Safety
-    /// The pointer must be valid and must outlive this reference
-    pub(crate) unsafe fn from_ptr(ptr: *mut cJSON) -> CJsonResult<Self> {
+    /// `ptr` must be null or a NUL-terminated string allocated by cJSON and not freed elsewhere
+    pub(crate) unsafe fn new(ptr: *mut c_char) -> Option<Self> {
         if ptr.is_null() {
-            Err(CJsonError::NullPointer)
+            None
         } else {
-            Ok(CJsonRef { ptr })
+            Some(COwnedStr(ptr))
         }
     }
 
-    /// Get the raw pointer (does not transfer ownership)
-    pub fn as_ptr(&self) -> *const cJSON {
-        self.ptr
+    /// Borrow the string
+    pub(crate) fn as_c_str(&self) -> &CStr {
+        unsafe { CStr::from_ptr(self.0) }
     }
 
-    /// Check if the item is a string
-    pub fn is_string(&self) -> bool {
-        unsafe { cJSON_IsString(self.ptr) != 0 }
+    /// Copy into a Rust string, replacing invalid UTF-8
+    pub(crate) fn to_string_lossy(&self) -> String {
+        self.as_c_str().to_string_lossy().into_owned()
     }
+}
 
-    /// Check if the item is a number
-    pub fn is_number(&self) -> bool {
-        unsafe { cJSON_IsNumber(self.ptr) != 0 }
+impl Drop for COwnedStr {
+    fn drop(&mut self) {
+        unsafe { cJSON_free(self.0 as *mut core::ffi::c_void) };
     }
+}
 
-    /// Check if the item is a boolean
-    pub fn is_bool(&self) -> bool {
-        unsafe { cJSON_IsBool(self.ptr) != 0 }
+/// Lazy iterator over the items of an array, created by `CJson::array_iter`
+pub struct ArrayIter<'a> {
+    next: *mut cJSON,
+    _marker: PhantomData<&'a CJson>,
+}
+
+impl Iterator for ArrayIter<'_> {
+    type Item = CJsonRef;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next.is_null() {
+            return None;
+        }
+        let item = CJsonRef { ptr: self.next };
+        self.next = unsafe { (*self.next).next };
+        Some(item)
     }
+}
 
-    /// Check if the item is null
-    pub fn is_null(&self) -> bool {
-        unsafe { cJSON_IsNull(self.ptr) != 0 }
+impl FusedIterator for ArrayIter<'_> {}
+
+/// Key lookup table over an object's members, created by `CJson::build_index`
+pub struct ObjectIndex<'a> {
+    members: BTreeMap<&'a str, *mut cJSON>,
+}
+
+impl ObjectIndex<'_> {
+    /// Get the member named `key` (case sensitive)
+    pub fn get(&self, key: &str) -> CJsonResult<CJsonRef> {
+        self.members
+            .get(key)
+            .map(|&ptr| CJsonRef { ptr })
+            .ok_or(CJsonError::NotFound)
     }
 
-    /// Check if the item is an array
-    pub fn is_array(&self) -> bool {
-        unsafe { cJSON_IsArray(self.ptr) != 0 }
+    /// Whether the object has a member named `key` (case sensitive)
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.members.contains_key(key)
     }
 
-    /// Check if the item is an object
-    pub fn is_object(&self) -> bool {
-        unsafe { cJSON_IsObject(self.ptr) != 0 }
+    /// Number of indexed keys
+    pub fn len(&self) -> usize {
+        self.members.len()
     }
 
-    /// Get string value
+    /// Whether no key was indexed
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+}
+
+/// Lazy iterator over mutable array items, created by `CJson::array_iter_mut`
+pub struct ArrayIterMut<'a> {
+    next: *mut cJSON,
+    _marker: PhantomData<&'a mut CJson>,
+}
+
+impl<'a> Iterator for ArrayIterMut<'a> {
+    type Item = CJsonMut<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next.is_null() {
+            return None;
+        }
+        let item = CJsonMut { item: CJsonRef { ptr: self.next }, _marker: PhantomData };
+        self.next = unsafe { (*self.next).next };
+        Some(item)
+    }
+}
+
+impl FusedIterator for ArrayIterMut<'_> {}
+
+/// Mutably borrowed cJSON item: reads through `CJsonRef`, writes values in place
+pub struct CJsonMut<'a> {
+    item: CJsonRef,
+    _marker: PhantomData<&'a mut CJson>,
+}
+
+impl CJsonMut<'_> {
+    /// Set the value of a number item
+    pub fn set_number_value(&mut self, value: f64) -> CJsonResult<()> {
+        if !self.item.is_number() {
+            return Err(CJsonError::TypeError);
+        }
+        unsafe { cJSON_SetNumberHelper(self.item.ptr, value) };
+        Ok(())
+    }
+
+    /// Set the value of a string item
+    pub fn set_string_value(&mut self, value: &str) -> CJsonResult<()> {
+        if !self.item.is_string() {
+            return Err(CJsonError::TypeError);
+        }
+        let c_value = CString::new(value)?;
+        let ret = unsafe { cJSON_SetValuestring(self.item.ptr, c_value.as_ptr()) };
+        if ret.is_null() {
+            return Err(CJsonError::AllocationError);
+        }
+        Ok(())
+    }
+
+    /// Set the value of a boolean item
+    pub fn set_bool_value(&mut self, value: bool) -> CJsonResult<()> {
+        if !self.item.is_bool() {
+            return Err(CJsonError::TypeError);
+        }
+        let item = unsafe { &mut *self.item.ptr };
+        item.type_ = (item.type_ & !(cJSON_False | cJSON_True)) | if value { cJSON_True } else { cJSON_False };
+        Ok(())
+    }
+}
+
+impl core::ops::Deref for CJsonMut<'_> {
+    type Target = CJsonRef;
+
+    fn deref(&self) -> &Self::Target {
+        &self.item
+    }
+}
+
+/// Borrowed reference to a cJSON item (does not own the pointer)
+#[derive(Debug)]
+pub struct CJsonRef {
+    ptr: *mut cJSON,
+}
+
+impl CJsonRef {
+    /// Borrow an item owned by C code outside this crate
+    ///
+    /// Nothing is freed when the returned reference goes away. Returns
+    /// `NullPointer` if `ptr` is null.
+    ///
+    /// # Safety
+    /// - `ptr` must point to a valid cJSON item for as long as the returned
+    ///   reference, or anything obtained from it, is used: its owner must not
+    ///   free, detach or replace it meanwhile.
+    /// - `CJsonRef` carries no lifetime, so the compiler cannot check this; keep
+    ///   the reference in a scope shorter than the owner's.
+    /// - Do not pass the reference to anything that takes ownership of it.
+    pub unsafe fn from_borrowed_ptr(ptr: *mut cJSON) -> CJsonResult<Self> {
+        unsafe { Self::from_ptr(ptr) }
+    }
+
+    /// Create a new CJsonRef from a raw pointer (does not take ownership)
+    /// 
+    /// # Safety
+    /// The pointer must be valid and must outlive this reference
+    pub(crate) unsafe fn from_ptr(ptr: *mut cJSON) -> CJsonResult<Self> {
+        if ptr.is_null() {
+            Err(CJsonError::NullPointer)
+        } else {
+            Ok(CJsonRef { ptr })
+        }
+    }
+
+    /// Get the raw pointer (does not transfer ownership)
+    pub fn as_ptr(&self) -> *const cJSON {
+        self.ptr
+    }
+
+    /// Check if the item is a string
+    pub fn is_string(&self) -> bool {
+        unsafe { cJSON_IsString(self.ptr) != 0 }
+    }
+
+    /// Check if the item is a number
+    pub fn is_number(&self) -> bool {
+        unsafe { cJSON_IsNumber(self.ptr) != 0 }
+    }
+
+    /// Check if the item is a boolean
+    pub fn is_bool(&self) -> bool {
+        unsafe { cJSON_IsBool(self.ptr) != 0 }
+    }
+
+    /// Check if the item is null
+    pub fn is_null(&self) -> bool {
+        unsafe { cJSON_IsNull(self.ptr) != 0 }
+    }
+
+    /// Check if the item is an array
+    pub fn is_array(&self) -> bool {
+        unsafe { cJSON_IsArray(self.ptr) != 0 }
+    }
+
+    /// Check if the item is an object
+    pub fn is_object(&self) -> bool {
+        unsafe { cJSON_IsObject(self.ptr) != 0 }
+    }
+
+    /// Name of the item's type, see `CJson::type_name`
+    pub fn type_name(&self) -> &'static str {
+        unsafe { type_name_ptr(self.ptr) }
+    }
+
+    /// Whether the item is a reference, see `CJson::is_reference`
+    pub fn is_reference(&self) -> bool {
+        (unsafe { raw_type_bits(self.ptr) } & cJSON_IsReference) != 0
+    }
+
+    /// Whether the item's key is a constant string, see `CJson::is_const_string`
+    pub fn is_const_string(&self) -> bool {
+        (unsafe { raw_type_bits(self.ptr) } & cJSON_StringIsConst) != 0
+    }
+
+    /// Raw bytes of the item's key, `None` if it is not an object member
+    ///
+    /// Unlike the `&str` keys of `visit_object`, keys that are not UTF-8 are
+    /// returned unchanged.
+    pub fn key_bytes(&self) -> Option<&[u8]> {
+        let key = unsafe { (*self.ptr).string };
+        if key.is_null() {
+            return None;
+        }
+        Some(unsafe { CStr::from_ptr(key) }.to_bytes())
+    }
+
+    /// Get string value
     pub fn get_string_value(&self) -> CJsonResult<String> {
         if !self.is_string() {
             return Err(CJsonError::TypeError);
@@ -668,303 +1691,2235 @@ impl CJsonRef {
         Ok(unsafe { CStr::from_ptr(c_str).to_string_lossy().into_owned() })
     }
 
-    /// Get number value as f64
-    pub fn get_number_value(&self) -> CJsonResult<f64> {
-        if !self.is_number() {
-            return Err(CJsonError::TypeError);
-        }
-        Ok(unsafe { cJSON_GetNumberValue(self.ptr) })
+    /// Get number value as f64 (also reads Raw nodes holding numeric text)
+    pub fn get_number_value(&self) -> CJsonResult<f64> {
+        unsafe { number_value_ptr(self.ptr) }
+    }
+
+    /// Get number value as i64 (fractions are truncated)
+    pub fn get_i64(&self) -> CJsonResult<i64> {
+        unsafe { i64_value_ptr(self.ptr) }
+    }
+
+    /// Get number value as u64 (fractions are truncated)
+    pub fn get_u64(&self) -> CJsonResult<u64> {
+        unsafe { u64_value_ptr(self.ptr) }
+    }
+
+    /// Get number value as i32 (fractions are truncated)
+    ///
+    /// cJSON saturates its `int` copy of the number, so values outside the
+    /// `i32` range return `NumberOutOfRange`; use `get_i64` for those.
+    pub fn get_int_value(&self) -> CJsonResult<i32> {
+        unsafe { int_value_ptr(self.ptr) }
+    }
+
+    /// Get boolean value
+    pub fn get_bool_value(&self) -> CJsonResult<bool> {
+        unsafe { bool_value_ptr(self.ptr) }
+    }
+
+    /// Get boolean value, also accepting numbers (non-zero is true)
+    pub fn as_bool_lenient(&self) -> CJsonResult<bool> {
+        if self.is_bool() {
+            self.get_bool_value()
+        } else if self.is_number() {
+            Ok(self.get_number_value()? != 0.0)
+        } else {
+            Err(CJsonError::TypeError)
+        }
+    }
+
+    /// Render a scalar as plain text: strings without quotes, numbers by
+    /// value, bools as "true"/"false", null as "null" and Raw nodes verbatim.
+    /// Arrays and objects return `TypeError`; use `print` for those.
+    pub fn to_display_string(&self) -> CJsonResult<String> {
+        unsafe { display_string_ptr(self.ptr) }
+    }
+
+    /// Get array size
+    pub fn get_array_size(&self) -> CJsonResult<usize> {
+        if !self.is_array() {
+            return Err(CJsonError::TypeError);
+        }
+        Ok(unsafe { cJSON_GetArraySize(self.ptr) as usize })
+    }
+
+    /// Whether an array or object has no children
+    pub fn is_empty(&self) -> CJsonResult<bool> {
+        if !self.is_array() && !self.is_object() {
+            return Err(CJsonError::TypeError);
+        }
+        Ok(unsafe { (*self.ptr).child }.is_null())
+    }
+
+    /// Get array item by index
+    pub fn get_array_item(&self, index: usize) -> CJsonResult<CJsonRef> {
+        if !self.is_array() {
+            return Err(CJsonError::TypeError);
+        }
+        let ptr = unsafe { cJSON_GetArrayItem(self.ptr, index as c_int) };
+        unsafe { CJsonRef::from_ptr(ptr) }.map_err(|_| CJsonError::NotFound)
+    }
+
+    /// Get the number of object members
+    pub fn object_size(&self) -> CJsonResult<usize> {
+        if !self.is_object() {
+            return Err(CJsonError::TypeError);
+        }
+        // cJSON_GetArraySize counts the children of any container
+        Ok(unsafe { cJSON_GetArraySize(self.ptr) as usize })
+    }
+
+    /// Get object item by key
+    pub fn get_object_item(&self, key: &str) -> CJsonResult<CJsonRef> {
+        if !self.is_object() {
+            return Err(CJsonError::TypeError);
+        }
+        let c_key = CString::new(key)?;
+        let ptr = unsafe { cJSON_GetObjectItem(self.ptr, c_key.as_ptr()) };
+        unsafe { CJsonRef::from_ptr(ptr) }.map_err(|_| CJsonError::NotFound)
+    }
+
+    /// Print the referenced sub-tree to JSON string (formatted)
+    pub fn print(&self) -> CJsonResult<String> {
+        let c_str = unsafe { COwnedStr::new(cJSON_Print(self.ptr)) }
+            .ok_or(CJsonError::AllocationError)?;
+        Ok(c_str.to_string_lossy())
+    }
+
+    /// Print the referenced sub-tree to JSON string (unformatted)
+    pub fn print_unformatted(&self) -> CJsonResult<String> {
+        let c_str = unsafe { COwnedStr::new(cJSON_PrintUnformatted(self.ptr)) }
+            .ok_or(CJsonError::AllocationError)?;
+        Ok(c_str.to_string_lossy())
+    }
+
+    /// Deep copy the referenced item into an independent owned `CJson`
+    pub fn to_owned(&self) -> CJsonResult<CJson> {
+        let ptr = unsafe { cJSON_Duplicate(self.ptr, 1) };
+        unsafe { CJson::from_ptr(ptr) }
+    }
+
+    /// Copy only the referenced node into an owned `CJson`
+    ///
+    /// Arrays and objects come back empty: children are never shared with the
+    /// source. Copying the raw struct instead would alias the child list, and
+    /// unless the copy is flagged with `cJSON_IsReference` both owners would
+    /// free the same children.
+    pub fn shallow_clone(&self) -> CJsonResult<CJson> {
+        let ptr = unsafe { cJSON_Duplicate(self.ptr, 0) };
+        unsafe { CJson::from_ptr(ptr) }
+    }
+
+    /// Collect the object's key/value pairs in insertion order
+    ///
+    /// The returned references point into the same tree as `self`.
+    pub fn object_entries(&self) -> CJsonResult<Vec<(String, CJsonRef)>> {
+        if !self.is_object() {
+            return Err(CJsonError::TypeError);
+        }
+        let mut entries = Vec::new();
+        unsafe {
+            visit_object_ptr(self.ptr, &mut |key, value| {
+                entries.push((String::from(key), CJsonRef { ptr: value.ptr }));
+                Ok(())
+            })
+        }?;
+        Ok(entries)
+    }
+
+    /// Same result as `CJson::structurally_eq`, for a borrowed item
+    pub fn structurally_eq(&self, other: &CJson, case_sensitive: bool) -> bool {
+        unsafe { structurally_eq_ptr(self.ptr, other.ptr, case_sensitive) }
+    }
+}
+
+/// Summarizes the tree, see `debug_summary`
+impl core::fmt::Debug for CJson {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.debug_summary(DEBUG_MAX_CHILDREN, DEBUG_MAX_STR))
+    }
+}
+
+/// Writes the referenced sub-tree as unformatted JSON
+impl Display for CJsonRef {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let text = self.print_unformatted().map_err(|_| core::fmt::Error)?;
+        f.write_str(&text)
+    }
+}
+
+// `let n: f64 = (&node).try_into()?;` for leaf nodes, through the matching
+// `get_*` accessor; a node of another type gives its `TypeError`
+macro_rules! try_from_node {
+    ($($t:ty => $get:ident),*) => {
+        $(
+            impl TryFrom<&CJson> for $t {
+                type Error = CJsonError;
+
+                fn try_from(node: &CJson) -> CJsonResult<Self> {
+                    node.$get()
+                }
+            }
+
+            impl TryFrom<&CJsonRef> for $t {
+                type Error = CJsonError;
+
+                fn try_from(node: &CJsonRef) -> CJsonResult<Self> {
+                    node.$get()
+                }
+            }
+        )*
+    };
+}
+
+try_from_node!(f64 => get_number_value, i64 => get_i64, bool => get_bool_value, String => get_string_value);
+
+/// Children shown per container by `Debug for CJson`
+const DEBUG_MAX_CHILDREN: usize = 8;
+
+/// Characters shown per string by `Debug for CJson`
+const DEBUG_MAX_STR: usize = 64;
+
+unsafe fn debug_summary_ptr(item: *const cJSON, max_children: usize, max_str: usize, out: &mut String) {
+    fn push_str_summary(text: &[u8], max_str: usize, out: &mut String) {
+        let text = String::from_utf8_lossy(text);
+        out.push('"');
+        for (i, c) in text.chars().enumerate() {
+            if i == max_str {
+                out.push('…');
+                break;
+            }
+            out.extend(c.escape_debug());
+        }
+        out.push('"');
+    }
+
+    let kind = unsafe { raw_type_bits(item) } & 0xFF;
+    if kind == cJSON_String {
+        let c_str = unsafe { cJSON_GetStringValue(item) };
+        if !c_str.is_null() {
+            push_str_summary(unsafe { CStr::from_ptr(c_str) }.to_bytes(), max_str, out);
+        }
+        return;
+    }
+    if kind != cJSON_Array && kind != cJSON_Object {
+        out.push_str(&unsafe { display_string_ptr(item) }.unwrap_or_default());
+        return;
+    }
+
+    let is_object = kind == cJSON_Object;
+    out.push(if is_object { '{' } else { '[' });
+    let mut child = unsafe { (*item).child };
+    let mut shown = 0;
+    while !child.is_null() && shown < max_children {
+        if shown > 0 {
+            out.push(',');
+        }
+        if is_object {
+            let key = unsafe { (*child).string };
+            if !key.is_null() {
+                push_str_summary(unsafe { CStr::from_ptr(key) }.to_bytes(), max_str, out);
+            }
+            out.push(':');
+        }
+        unsafe { debug_summary_ptr(child, max_children, max_str, out) };
+        shown += 1;
+        child = unsafe { (*child).next };
+    }
+    let mut more = 0;
+    while !child.is_null() {
+        more += 1;
+        child = unsafe { (*child).next };
+    }
+    if more > 0 {
+        if shown > 0 {
+            out.push(',');
+        }
+        let _ = core::fmt::Write::write_fmt(out, format_args!("… ({} more)", more));
+    }
+    out.push(if is_object { '}' } else { ']' });
+}
+
+/// Visit each member of the object `item` with its borrowed key
+unsafe fn visit_object_ptr<F>(item: *mut cJSON, f: &mut F) -> CJsonResult<()>
+where
+    F: FnMut(&str, &CJsonRef) -> CJsonResult<()>,
+{
+    let mut child = unsafe { (*item).child };
+    while !child.is_null() {
+        let key_ptr = unsafe { (*child).string };
+        if key_ptr.is_null() {
+            return Err(CJsonError::NullPointer);
+        }
+        let key = unsafe { CStr::from_ptr(key_ptr) }
+            .to_str()
+            .map_err(|_| CJsonError::InvalidUtf8)?;
+        f(key, &CJsonRef { ptr: child })?;
+        child = unsafe { (*child).next };
+    }
+    Ok(())
+}
+
+/// Pre-order walk below `item`, keeping `path` as its JSON Pointer
+unsafe fn walk_ptr<F>(item: *mut cJSON, path: &mut String, f: &mut F) -> CJsonResult<()>
+where
+    F: FnMut(&str, &CJsonRef) -> CJsonResult<()>,
+{
+    f(path, &CJsonRef { ptr: item })?;
+
+    let is_object = unsafe { cJSON_IsObject(item) != 0 };
+    let mut child = unsafe { (*item).child };
+    let mut index = 0usize;
+    while !child.is_null() {
+        let len = path.len();
+        path.push('/');
+        if is_object {
+            let key_ptr = unsafe { (*child).string };
+            if key_ptr.is_null() {
+                return Err(CJsonError::NullPointer);
+            }
+            let key = unsafe { CStr::from_ptr(key_ptr) }
+                .to_str()
+                .map_err(|_| CJsonError::InvalidUtf8)?;
+            for c in key.chars() {
+                match c {
+                    '~' => path.push_str("~0"),
+                    '/' => path.push_str("~1"),
+                    _ => path.push(c),
+                }
+            }
+        } else {
+            use core::fmt::Write;
+            let _ = write!(path, "{}", index);
+        }
+        unsafe { walk_ptr(child, path, f)? };
+        path.truncate(len);
+        child = unsafe { (*child).next };
+        index += 1;
+    }
+    Ok(())
+}
+
+/// Read a boolean, refusing anything whose type is not exactly true or false
+unsafe fn bool_value_ptr(item: *const cJSON) -> CJsonResult<bool> {
+    let kind = unsafe { raw_type_bits(item) } & 0xFF;
+    if kind == cJSON_True {
+        Ok(true)
+    } else if kind == cJSON_False {
+        Ok(false)
+    } else {
+        // Includes `cJSON_Invalid` (a node never given a type) and corrupt bits such
+        // as true and false at once, which `cJSON_IsBool` accepts and `cJSON_IsTrue` reads as false
+        Err(CJsonError::TypeError)
+    }
+}
+
+/// Full `type_` of an item: the type in the low byte plus the
+/// `cJSON_IsReference` and `cJSON_StringIsConst` flags
+unsafe fn raw_type_bits(item: *const cJSON) -> c_int {
+    unsafe { (*item).type_ }
+}
+
+unsafe fn display_string_ptr(item: *const cJSON) -> CJsonResult<String> {
+    let kind = unsafe { raw_type_bits(item) } & 0xFF;
+    if kind == cJSON_Array || kind == cJSON_Object {
+        return Err(CJsonError::TypeError);
+    }
+    if kind == cJSON_String {
+        let c_str = unsafe { cJSON_GetStringValue(item) };
+        if c_str.is_null() {
+            return Err(CJsonError::NullPointer);
+        }
+        return Ok(unsafe { CStr::from_ptr(c_str).to_string_lossy().into_owned() });
+    }
+    let c_str = unsafe { COwnedStr::new(cJSON_PrintUnformatted(item)) }
+        .ok_or(CJsonError::AllocationError)?;
+    Ok(c_str.to_string_lossy())
+}
+
+unsafe fn type_name_ptr(item: *const cJSON) -> &'static str {
+    let kind = unsafe { raw_type_bits(item) } & 0xFF;
+    if kind == cJSON_False || kind == cJSON_True {
+        "bool"
+    } else if kind == cJSON_NULL {
+        "null"
+    } else if kind == cJSON_Number {
+        "number"
+    } else if kind == cJSON_String {
+        "string"
+    } else if kind == cJSON_Array {
+        "array"
+    } else if kind == cJSON_Object {
+        "object"
+    } else if kind == cJSON_Raw {
+        "raw"
+    } else {
+        "invalid"
+    }
+}
+
+/// Numeric text of a Raw node, if it holds a number
+unsafe fn raw_number_text<'a>(item: *const cJSON) -> Option<&'a str> {
+    if unsafe { cJSON_IsRaw(item) == 0 || (*item).valuestring.is_null() } {
+        return None;
+    }
+    let text = unsafe { CStr::from_ptr((*item).valuestring) }.to_str().ok()?.trim();
+    text.parse::<f64>().ok().map(|_| text)
+}
+
+/// Value of a number, or of a Raw node holding numeric text
+unsafe fn number_value_ptr(item: *const cJSON) -> CJsonResult<f64> {
+    if unsafe { cJSON_IsNumber(item) != 0 } {
+        return Ok(unsafe { cJSON_GetNumberValue(item) });
+    }
+    let text = unsafe { raw_number_text(item) }.ok_or(CJsonError::TypeError)?;
+    text.parse::<f64>().map_err(|_| CJsonError::TypeError)
+}
+
+/// `valueint` of a number, refused when cJSON had to clamp it
+unsafe fn int_value_ptr(item: *const cJSON) -> CJsonResult<i32> {
+    if unsafe { cJSON_IsNumber(item) == 0 } {
+        return Err(CJsonError::TypeError);
+    }
+    let (value, int) = unsafe { ((*item).valuedouble, (*item).valueint) };
+    // Within one of the `int` bounds the truncated number fits (`f64::trunc`
+    // is not in core); cJSON clamps anything further out
+    let fits = value > i32::MIN as f64 - 1.0 && value < i32::MAX as f64 + 1.0;
+    if fits && value as i32 == int {
+        Ok(int)
+    } else {
+        Err(CJsonError::NumberOutOfRange { value, min: i32::MIN as f64, max: i32::MAX as f64 })
+    }
+}
+
+/// Integer value of a number or Raw node; Raw integers are read exactly
+unsafe fn i64_value_ptr(item: *const cJSON) -> CJsonResult<i64> {
+    if let Some(value) = unsafe { raw_number_text(item) }.and_then(|text| text.parse::<i64>().ok()) {
+        return Ok(value);
+    }
+    let value = unsafe { number_value_ptr(item)? };
+    // 2^63 is not representable as i64, so compare against it exclusively
+    if (-9_223_372_036_854_775_808.0..9_223_372_036_854_775_808.0).contains(&value) {
+        Ok(value as i64)
+    } else {
+        Err(CJsonError::NumberOutOfRange { value, min: i64::MIN as f64, max: i64::MAX as f64 })
+    }
+}
+
+/// Unsigned integer value of a number or Raw node; Raw integers are read exactly
+unsafe fn u64_value_ptr(item: *const cJSON) -> CJsonResult<u64> {
+    if let Some(value) = unsafe { raw_number_text(item) }.and_then(|text| text.parse::<u64>().ok()) {
+        return Ok(value);
+    }
+    let value = unsafe { number_value_ptr(item)? };
+    if value > -1.0 && value < 18_446_744_073_709_551_616.0 {
+        Ok(value as u64)
+    } else {
+        Err(CJsonError::NumberOutOfRange { value, min: 0.0, max: u64::MAX as f64 })
+    }
+}
+
+/// Recursively check keys and string values below `item`
+unsafe fn validate_utf8_ptr(item: *const cJSON) -> CJsonResult<()> {
+    let node = unsafe { &*item };
+    if !node.string.is_null() {
+        unsafe { CStr::from_ptr(node.string) }.to_str().map_err(|_| CJsonError::InvalidUtf8)?;
+    }
+    if unsafe { cJSON_IsString(item) != 0 || cJSON_IsRaw(item) != 0 } && !node.valuestring.is_null() {
+        unsafe { CStr::from_ptr(node.valuestring) }.to_str().map_err(|_| CJsonError::InvalidUtf8)?;
+    }
+    let mut child = node.child;
+    while !child.is_null() {
+        unsafe { validate_utf8_ptr(child)? };
+        child = unsafe { (*child).next };
+    }
+    Ok(())
+}
+
+/// 1-based line and column of byte `offset` in `source`
+///
+/// Lines are counted by `\n`; the column counts characters, so multi-byte
+/// UTF-8 sequences count once. `offset` is clamped to the length of `source`.
+pub fn offset_to_line_col(source: &str, offset: usize) -> (usize, usize) {
+    let mut offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let head = &source[..offset];
+    let line = head.matches('\n').count() + 1;
+    let line_start = head.rfind('\n').map_or(0, |i| i + 1);
+    (line, head[line_start..].chars().count() + 1)
+}
+
+/// True when the input holds nothing but whitespace
+fn is_blank(json: &[u8]) -> bool {
+    json.iter().all(u8::is_ascii_whitespace)
+}
+
+/// Remove comments and trailing commas outside string literals
+fn strip_relaxed(json: &str) -> String {
+    let bytes = json.as_bytes();
+    let mut out = String::with_capacity(json.len());
+    // Start of the text not yet copied to `out`
+    let mut copied = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                i += 1;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                out.push_str(&json[copied..i]);
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+                copied = i;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                out.push_str(&json[copied..i]);
+                i += 2;
+                while i < bytes.len() && !(bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/')) {
+                    i += 1;
+                }
+                i = (i + 2).min(bytes.len());
+                // Keep tokens on either side apart
+                out.push(' ');
+                copied = i;
+            }
+            b']' | b'}' => {
+                out.push_str(&json[copied..i]);
+                copied = i;
+                let trimmed = out.trim_end_matches(|c: char| c.is_ascii_whitespace());
+                if trimmed.ends_with(',') {
+                    out.truncate(trimmed.len() - 1);
+                }
+                i += 1;
+            }
+            _ => i += 1,
+        }
+    }
+    out.push_str(&json[copied.min(json.len())..]);
+    out
+}
+
+/// Number tokens of a JSON text in document order (strings are skipped)
+fn number_tokens(json: &str) -> Vec<&str> {
+    let bytes = json.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                i += 1;
+            }
+            b'-' | b'0'..=b'9' => {
+                let start = i;
+                while i < bytes.len() && matches!(bytes[i], b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') {
+                    i += 1;
+                }
+                tokens.push(&json[start..i]);
+            }
+            _ => i += 1,
+        }
+    }
+    tokens
+}
+
+/// Swap every native number below `item` for a Raw node holding `text(value)`;
+/// numbers for which `text` gives `None` are left as they are
+unsafe fn numbers_to_raw_ptr<F>(item: *mut cJSON, text: &mut F) -> CJsonResult<()>
+where
+    F: FnMut(f64) -> CJsonResult<Option<String>>,
+{
+    let mut child = unsafe { (*item).child };
+    while !child.is_null() {
+        if unsafe { cJSON_IsNumber(child) != 0 } {
+            if let Some(token) = text(unsafe { (*child).valuedouble })? {
+                let raw = CJson::create_raw(&token)?.into_raw();
+                unsafe {
+                    // Hand the key over so the replacement keeps its name
+                    (*raw).string = (*child).string;
+                    (*raw).type_ |= (*child).type_ & cJSON_StringIsConst;
+                    (*child).string = ptr::null_mut();
+                    cJSON_ReplaceItemViaPointer(item, child, raw);
+                }
+                child = raw;
+            }
+        } else {
+            unsafe { numbers_to_raw_ptr(child, text)? };
+        }
+        child = unsafe { (*child).next };
+    }
+    Ok(())
+}
+
+/// Shortest decimal that parses back to exactly `value`, `None` for NaN/infinity
+fn round_trip_text(value: f64) -> Option<String> {
+    if !value.is_finite() {
+        return None;
+    }
+    let abs = value.abs();
+    // Exponent form keeps huge and tiny values short
+    if abs != 0.0 && !(1e-5..1e16).contains(&abs) {
+        Some(format!("{:e}", value))
+    } else {
+        Some(format!("{}", value))
+    }
+}
+
+/// Structural equality with numbers compared by value regardless of storage
+unsafe fn compare_normalized_ptr(a: *mut cJSON, b: *mut cJSON) -> bool {
+    if let (Ok(x), Ok(y)) = unsafe { (number_value_ptr(a), number_value_ptr(b)) } {
+        return x == y;
+    }
+    let arrays = unsafe { cJSON_IsArray(a) != 0 && cJSON_IsArray(b) != 0 };
+    let objects = unsafe { cJSON_IsObject(a) != 0 && cJSON_IsObject(b) != 0 };
+    if !arrays && !objects {
+        return unsafe { cJSON_Compare(a, b, 1) != 0 };
+    }
+    if unsafe { cJSON_GetArraySize(a) != cJSON_GetArraySize(b) } {
+        return false;
+    }
+
+    let mut child = unsafe { (*a).child };
+    let mut partner = unsafe { (*b).child };
+    while !child.is_null() {
+        if objects {
+            partner = unsafe { cJSON_GetObjectItemCaseSensitive(b, (*child).string) };
+        }
+        if partner.is_null() || !unsafe { compare_normalized_ptr(child, partner) } {
+            return false;
+        }
+        child = unsafe { (*child).next };
+        if arrays {
+            partner = unsafe { (*partner).next };
+        }
+    }
+    true
+}
+
+/// Object member named like `key`, first match as in cJSON
+unsafe fn object_member_ptr(object: *const cJSON, key: *const c_char, case_sensitive: bool) -> *mut cJSON {
+    if key.is_null() {
+        return ptr::null_mut();
+    }
+    unsafe {
+        if case_sensitive {
+            cJSON_GetObjectItemCaseSensitive(object, key)
+        } else {
+            cJSON_GetObjectItem(object, key)
+        }
+    }
+}
+
+/// Structural equality with the semantics of `cJSON_Compare`
+unsafe fn structurally_eq_ptr(a: *const cJSON, b: *const cJSON, case_sensitive: bool) -> bool {
+    if a.is_null() || b.is_null() {
+        return false;
+    }
+    let kind = unsafe { raw_type_bits(a) } & 0xFF;
+    if kind != unsafe { raw_type_bits(b) } & 0xFF {
+        return false;
+    }
+    let valid = [cJSON_False, cJSON_True, cJSON_NULL, cJSON_Number, cJSON_String, cJSON_Raw, cJSON_Array, cJSON_Object];
+    if !valid.contains(&kind) {
+        return false;
+    }
+    if ptr::eq(a, b) || kind == cJSON_False || kind == cJSON_True || kind == cJSON_NULL {
+        return true;
+    }
+
+    if kind == cJSON_Number {
+        let (x, y) = unsafe { ((*a).valuedouble, (*b).valuedouble) };
+        // Relative tolerance of cJSON's compare_double
+        let max = if x.abs() > y.abs() { x.abs() } else { y.abs() };
+        return (x - y).abs() <= max * f64::EPSILON;
+    }
+    if kind == cJSON_String || kind == cJSON_Raw {
+        let (x, y) = unsafe { ((*a).valuestring, (*b).valuestring) };
+        if x.is_null() || y.is_null() {
+            return false;
+        }
+        return unsafe { CStr::from_ptr(x) == CStr::from_ptr(y) };
+    }
+
+    let mut x = unsafe { (*a).child };
+    if kind == cJSON_Array {
+        let mut y = unsafe { (*b).child };
+        while !x.is_null() && !y.is_null() {
+            if !unsafe { structurally_eq_ptr(x, y, case_sensitive) } {
+                return false;
+            }
+            unsafe {
+                x = (*x).next;
+                y = (*y).next;
+            }
+        }
+        // Both ran out together only if the lengths match
+        return x.is_null() && y.is_null();
+    }
+
+    while !x.is_null() {
+        let partner = unsafe { object_member_ptr(b, (*x).string, case_sensitive) };
+        if !unsafe { structurally_eq_ptr(x, partner, case_sensitive) } {
+            return false;
+        }
+        x = unsafe { (*x).next };
+    }
+    // Every member of `b` must exist in `a`. The first member of `b` with a
+    // given key was already compared above, only duplicates are left to check.
+    let mut y = unsafe { (*b).child };
+    while !y.is_null() {
+        let key = unsafe { (*y).string };
+        let partner = unsafe { object_member_ptr(a, key, case_sensitive) };
+        if partner.is_null() {
+            return false;
+        }
+        let first = unsafe { object_member_ptr(b, key, case_sensitive) };
+        if !ptr::eq(first, y) && !unsafe { structurally_eq_ptr(y, partner, case_sensitive) } {
+            return false;
+        }
+        y = unsafe { (*y).next };
+    }
+    true
+}
+
+/// Structural equality where array order does not matter
+unsafe fn compare_unordered_ptr(a: *mut cJSON, b: *mut cJSON, case_sensitive: bool) -> bool {
+    let arrays = unsafe { cJSON_IsArray(a) != 0 && cJSON_IsArray(b) != 0 };
+    let objects = unsafe { cJSON_IsObject(a) != 0 && cJSON_IsObject(b) != 0 };
+    if !arrays && !objects {
+        return unsafe { cJSON_Compare(a, b, if case_sensitive { 1 } else { 0 }) != 0 };
+    }
+    let len = unsafe { cJSON_GetArraySize(a) };
+    if len != unsafe { cJSON_GetArraySize(b) } {
+        return false;
+    }
+
+    let mut matched = alloc::vec![false; len as usize];
+    let mut child = unsafe { (*a).child };
+    while !child.is_null() {
+        let found = if arrays {
+            let mut candidate = unsafe { (*b).child };
+            let mut index = 0;
+            let mut found = false;
+            while !candidate.is_null() {
+                if !matched[index] && unsafe { compare_unordered_ptr(child, candidate, case_sensitive) } {
+                    matched[index] = true;
+                    found = true;
+                    break;
+                }
+                candidate = unsafe { (*candidate).next };
+                index += 1;
+            }
+            found
+        } else {
+            let key = unsafe { (*child).string };
+            let partner = unsafe {
+                if case_sensitive {
+                    cJSON_GetObjectItemCaseSensitive(b, key)
+                } else {
+                    cJSON_GetObjectItem(b, key)
+                }
+            };
+            !partner.is_null() && unsafe { compare_unordered_ptr(child, partner, case_sensitive) }
+        };
+        if !found {
+            return false;
+        }
+        child = unsafe { (*child).next };
+    }
+    true
+}
+
+/// Recursively enforce `ParseLimits` below `item`, sitting at `depth` containers
+unsafe fn check_limits_ptr(item: *const cJSON, depth: usize, limits: &ParseLimits, nodes: &mut usize) -> CJsonResult<()> {
+    *nodes += 1;
+    if *nodes > limits.max_nodes {
+        return Err(CJsonError::NodeLimitExceeded { max: limits.max_nodes });
+    }
+    let is_object = unsafe { cJSON_IsObject(item) != 0 };
+    if !is_object && unsafe { cJSON_IsArray(item) == 0 } {
+        return Ok(());
+    }
+    if depth + 1 > limits.max_depth {
+        return Err(CJsonError::DepthLimitExceeded { max: limits.max_depth });
+    }
+    let mut keys = BTreeSet::new();
+    let mut child = unsafe { (*item).child };
+    while !child.is_null() {
+        if is_object && limits.forbid_duplicate_keys {
+            let key_ptr = unsafe { (*child).string };
+            if !key_ptr.is_null() {
+                let key = unsafe { CStr::from_ptr(key_ptr) };
+                if !keys.insert(key) {
+                    return Err(CJsonError::DuplicateKey { key: key.to_string_lossy().into_owned() });
+                }
+            }
+        }
+        unsafe { check_limits_ptr(child, depth + 1, limits, nodes)? };
+        child = unsafe { (*child).next };
+    }
+    Ok(())
+}
+
+/// Get the cJSON library version
+#[allow(dead_code)]
+pub fn version() -> String {
+    let c_str = unsafe { cJSON_Version() };
+    unsafe { CStr::from_ptr(c_str).to_string_lossy().into_owned() }
+}
+
+/// Get the last parse error pointer
+#[allow(dead_code)]
+pub fn get_error_ptr() -> Option<String> {
+    let c_str = unsafe { cJSON_GetErrorPtr() };
+    if c_str.is_null() {
+        None
+    } else {
+        Some(unsafe { CStr::from_ptr(c_str).to_string_lossy().into_owned() })
+    }
+}
+
+/// Minify a JSON string in place
+#[allow(dead_code)]
+pub fn minify(json: &mut String) {
+    let c_str = CString::new(json.as_str()).expect("CString conversion failed");
+    unsafe {
+        let ptr = c_str.as_ptr() as *mut c_char;
+        cJSON_Minify(ptr);
+        *json = CStr::from_ptr(ptr).to_string_lossy().into_owned();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_simple_object() {
+        let json = r#"{"name":"John","age":30}"#;
+        let parsed = CJson::parse(json).unwrap();
+        assert!(parsed.is_object());
+    }
+
+    #[test]
+    fn test_parse_array() {
+        let json = r#"[1,2,3,4,5]"#;
+        let parsed = CJson::parse(json).unwrap();
+        assert!(parsed.is_array());
+        assert_eq!(parsed.get_array_size().unwrap(), 5);
+    }
+
+    #[test]
+    fn test_create_and_get_string() {
+        let json = CJson::create_string("Hello, World!").unwrap();
+        assert!(json.is_string());
+        assert_eq!(json.get_string_value().unwrap(), "Hello, World!");
+    }
+
+    #[test]
+    fn test_create_and_get_number() {
+        let json = CJson::create_number(42.5).unwrap();
+        assert!(json.is_number());
+        assert_eq!(json.get_number_value().unwrap(), 42.5);
+    }
+
+    #[test]
+    fn test_create_and_get_bool() {
+        let json_true = CJson::create_true().unwrap();
+        assert!(json_true.is_true());
+        assert!(json_true.is_bool());
+        assert_eq!(json_true.get_bool_value().unwrap(), true);
+
+        let json_false = CJson::create_false().unwrap();
+        assert!(json_false.is_false());
+        assert!(json_false.is_bool());
+        assert_eq!(json_false.get_bool_value().unwrap(), false);
+    }
+
+    #[test]
+    fn test_create_null() {
+        let json = CJson::create_null().unwrap();
+        assert!(json.is_null());
+    }
+
+    #[test]
+    fn test_create_object_and_add_items() {
+        let mut obj = CJson::create_object().unwrap();
+        obj.add_string_to_object("name", "Alice").unwrap();
+        obj.add_number_to_object("age", 25.0).unwrap();
+        obj.add_bool_to_object("active", true).unwrap();
+
+        assert!(obj.is_object());
+        assert!(obj.has_object_item("name"));
+        assert!(obj.has_object_item("age"));
+        assert!(obj.has_object_item("active"));
+
+        let name = obj.get_object_item("name").unwrap();
+        assert_eq!(name.get_string_value().unwrap(), "Alice");
+
+        let age = obj.get_object_item("age").unwrap();
+        assert_eq!(age.get_number_value().unwrap(), 25.0);
+    }
+
+    #[test]
+    fn test_create_array_and_add_items() {
+        let mut arr = CJson::create_array().unwrap();
+        arr.add_item_to_array(CJson::create_number(1.0).unwrap()).unwrap();
+        arr.add_item_to_array(CJson::create_number(2.0).unwrap()).unwrap();
+        arr.add_item_to_array(CJson::create_number(3.0).unwrap()).unwrap();
+
+        assert!(arr.is_array());
+        assert_eq!(arr.get_array_size().unwrap(), 3);
+
+        let item = arr.get_array_item(1).unwrap();
+        assert_eq!(item.get_number_value().unwrap(), 2.0);
+    }
+
+    #[test]
+    fn test_print_formatted() {
+        let mut obj = CJson::create_object().unwrap();
+        obj.add_string_to_object("key", "value").unwrap();
+        
+        let json_str = obj.print().unwrap();
+        assert!(json_str.contains("key"));
+        assert!(json_str.contains("value"));
+    }
+
+    #[test]
+    fn test_print_unformatted() {
+        let mut obj = CJson::create_object().unwrap();
+        obj.add_string_to_object("key", "value").unwrap();
+        
+        let json_str = obj.print_unformatted().unwrap();
+        assert!(json_str.contains("key"));
+        assert!(json_str.contains("value"));
+        assert!(!json_str.contains("\n")); // No newlines in unformatted
+    }
+
+    #[test]
+    fn test_duplicate() {
+        let original = CJson::create_string("test").unwrap();
+        let duplicate = original.duplicate(true).unwrap();
+        
+        assert_eq!(
+            original.get_string_value().unwrap(),
+            duplicate.get_string_value().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_compare() {
+        let json1 = CJson::create_number(42.0).unwrap();
+        let json2 = CJson::create_number(42.0).unwrap();
+        let json3 = CJson::create_number(43.0).unwrap();
+
+        assert!(json1.compare(&json2, true));
+        assert!(!json1.compare(&json3, true));
+    }
+
+    #[test]
+    fn test_create_int_array() {
+        let values = [1, 2, 3, 4, 5];
+        let arr = CJson::create_int_array(&values).unwrap();
+        
+        assert!(arr.is_array());
+        assert_eq!(arr.get_array_size().unwrap(), 5);
+    }
+
+    #[test]
+    fn test_create_double_array() {
+        let values = [1.1, 2.2, 3.3];
+        let arr = CJson::create_double_array(&values).unwrap();
+        
+        assert!(arr.is_array());
+        assert_eq!(arr.get_array_size().unwrap(), 3);
+    }
+
+    #[test]
+    #[ignore] // Temporarily disabled due to potential double free issue
+    fn test_create_string_array() {
+        let values = ["foo", "bar", "baz"];
+        let arr = CJson::create_string_array(&values).unwrap();
+        
+        assert!(arr.is_array());
+        assert_eq!(arr.get_array_size().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_delete_item_from_array() {
+        let mut arr = CJson::create_array().unwrap();
+        arr.add_item_to_array(CJson::create_number(1.0).unwrap()).unwrap();
+        arr.add_item_to_array(CJson::create_number(2.0).unwrap()).unwrap();
+        arr.add_item_to_array(CJson::create_number(3.0).unwrap()).unwrap();
+
+        assert_eq!(arr.get_array_size().unwrap(), 3);
+        arr.delete_item_from_array(1).unwrap();
+        assert_eq!(arr.get_array_size().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_delete_item_from_object() {
+        let mut obj = CJson::create_object().unwrap();
+        obj.add_string_to_object("key1", "value1").unwrap();
+        obj.add_string_to_object("key2", "value2").unwrap();
+
+        assert!(obj.has_object_item("key1"));
+        obj.delete_item_from_object("key1").unwrap();
+        assert!(!obj.has_object_item("key1"));
+        assert!(obj.has_object_item("key2"));
+    }
+
+    #[test]
+    fn test_parse_nested_object() {
+        let json = r#"{"person":{"name":"John","age":30}}"#;
+        let parsed = CJson::parse(json).unwrap();
+        
+        let person = parsed.get_object_item("person").unwrap();
+        assert!(person.is_object());
+        
+        let name = person.get_object_item("name").unwrap();
+        assert_eq!(name.get_string_value().unwrap(), "John");
+    }
+
+    #[test]
+    fn test_type_error() {
+        let json = CJson::create_string("not a number").unwrap();
+        assert!(json.get_number_value().is_err());
+    }
+
+    #[test]
+    fn test_not_found_error() {
+        let obj = CJson::create_object().unwrap();
+        assert!(obj.get_object_item("nonexistent").is_err());
+    }
+
+    #[test]
+    fn test_parse_with_length() {
+        let json = r#"{"key":"value"}"#;
+        let parsed = CJson::parse_with_length(json, json.len()).unwrap();
+        assert!(parsed.is_object());
+
+        let prefix = CJson::parse_with_length(r#"{"a":1}trailing"#, 7).unwrap();
+        assert!(prefix.is_object());
+        prefix.drop();
+
+        // Length past the end of the input is clamped
+        let clamped = CJson::parse_with_length("[1,2]", 1000).unwrap();
+        assert_eq!(clamped.get_array_size().unwrap(), 2);
+        clamped.drop();
+    }
+
+    #[test]
+    fn test_case_sensitive_get() {
+        let mut obj = CJson::create_object().unwrap();
+        obj.add_string_to_object("Key", "value").unwrap();
+        
+        assert!(obj.get_object_item_case_sensitive("Key").is_ok());
+        assert!(obj.get_object_item_case_sensitive("key").is_err());
+    }
+
+    #[test]
+    fn test_printed_size() {
+        let docs = [
+            r#"{"name":"John","age":30}"#,
+            r#"[1,2,3,{"nested":[true,false,null]}]"#,
+            r#""héllo""#,
+            r#"{}"#,
+        ];
+        for doc in docs.iter() {
+            let json = CJson::parse(doc).unwrap();
+            assert_eq!(json.printed_size(true).unwrap(), json.print().unwrap().len());
+            assert_eq!(json.printed_size(false).unwrap(), json.print_unformatted().unwrap().len());
+            json.drop();
+        }
+    }
+
+    #[test]
+    fn test_visit_object() {
+        let json = CJson::parse(r#"{"a":1,"b":2.5,"c":"skip","d":3}"#).unwrap();
+
+        let mut sum = 0.0;
+        let mut keys = 0;
+        json.visit_object(|_key, value| {
+            keys += 1;
+            if value.is_number() {
+                sum += value.get_number_value()?;
+            }
+            Ok(())
+        }).unwrap();
+        assert_eq!(keys, 4);
+        assert_eq!(sum, 6.5);
+
+        // Stops at the first error
+        let mut visited = 0;
+        let ret = json.visit_object(|key, _value| {
+            visited += 1;
+            if key == "b" { Err(CJsonError::InvalidOperation) } else { Ok(()) }
+        });
+        assert_eq!(ret, Err(CJsonError::InvalidOperation));
+        assert_eq!(visited, 2);
+
+        let arr = CJson::parse("[1,2]").unwrap();
+        assert_eq!(arr.visit_object(|_, _| Ok(())), Err(CJsonError::TypeError));
+
+        json.drop();
+        arr.drop();
+    }
+
+    #[test]
+    fn test_array_from_iter() {
+        let numbers = CJson::number_array_from_iter((1..=4).map(|i| i as f64 * 0.5)).unwrap();
+        assert_eq!(numbers.get_array_size().unwrap(), 4);
+        assert_eq!(numbers.get_array_item(3).unwrap().get_number_value().unwrap(), 2.0);
+
+        let owned: Vec<String> = vec![String::from("foo"), String::from("bar")];
+        let strings = CJson::string_array_from_iter(owned).unwrap();
+        assert_eq!(strings.print_unformatted().unwrap(), r#"["foo","bar"]"#);
+
+        let empty_numbers = CJson::number_array_from_iter(Vec::<f64>::new()).unwrap();
+        assert!(empty_numbers.is_array());
+        assert_eq!(empty_numbers.get_array_size().unwrap(), 0);
+
+        let empty_strings = CJson::string_array_from_iter(core::iter::empty::<&str>()).unwrap();
+        assert_eq!(empty_strings.print_unformatted().unwrap(), "[]");
+
+        assert_eq!(
+            CJson::string_array_from_iter(["ok", "bad\0"]).unwrap_err(),
+            CJsonError::InteriorNul { position: 3 }
+        );
+
+        numbers.drop();
+        strings.drop();
+        empty_numbers.drop();
+        empty_strings.drop();
+    }
+
+    #[test]
+    fn test_add_item_to_object_cs() {
+        let mut obj = CJson::create_object().unwrap();
+        obj.add_item_to_object_cs(c"port", CJson::create_number(123.0).unwrap()).unwrap();
+        obj.add_item_to_object_cs(c"server", CJson::create_string("pool.ntp.org").unwrap()).unwrap();
+        obj.add_item_to_object_cs(c"enabled", CJson::create_true().unwrap()).unwrap();
+
+        assert_eq!(
+            obj.print_unformatted().unwrap(),
+            r#"{"port":123,"server":"pool.ntp.org","enabled":true}"#
+        );
+        assert_eq!(obj.get_object_item("server").unwrap().get_string_value().unwrap(), "pool.ntp.org");
+
+        // Deleting must not try to free the constant keys
+        obj.drop();
+    }
+
+    #[test]
+    fn test_as_bool_lenient() {
+        let json = CJson::parse(r#"{"t":true,"zero":0,"one":1,"x":"x"}"#).unwrap();
+
+        assert_eq!(json.get_object_item("t").unwrap().as_bool_lenient(), Ok(true));
+        assert_eq!(json.get_object_item("zero").unwrap().as_bool_lenient(), Ok(false));
+        assert_eq!(json.get_object_item("one").unwrap().as_bool_lenient(), Ok(true));
+        assert_eq!(json.get_object_item("x").unwrap().as_bool_lenient(), Err(CJsonError::TypeError));
+
+        // get_bool_value stays strict
+        assert_eq!(json.get_object_item("one").unwrap().get_bool_value(), Err(CJsonError::TypeError));
+
+        let one = CJson::create_number(1.0).unwrap();
+        assert_eq!(one.as_bool_lenient(), Ok(true));
+        assert_eq!(CJson::create_false().unwrap().as_bool_lenient(), Ok(false));
+
+        json.drop();
+        one.drop();
+    }
+
+    #[test]
+    fn test_validate_utf8() {
+        let json = CJson::parse(r#"{"name":"h\u00e9llo","list":["\ud83d\ude00",{"k\u00e8y":1}]}"#).unwrap();
+        assert_eq!(json.validate_utf8(), Ok(()));
+
+        // cJSON refuses a lone surrogate escape at parse time
+        assert!(CJson::parse(r#"{"bad":"\uD800"}"#).is_err());
+
+        // Raw bytes coming from C are not checked by cJSON
+        let mut obj = CJson::create_object().unwrap();
+        let mut list = CJson::create_array().unwrap();
+        let bad = unsafe { CJson::from_ptr(cJSON_CreateString(c"ab\x80cd".as_ptr())) }.unwrap();
+        list.add_item_to_array(bad).unwrap();
+        obj.add_item_to_object("list", list).unwrap();
+        assert_eq!(obj.validate_utf8(), Err(CJsonError::InvalidUtf8));
+
+        // Invalid key
+        let mut obj2 = CJson::create_object().unwrap();
+        let value = CJson::create_null().unwrap().into_raw();
+        unsafe { cJSON_AddItemToObject(obj2.as_mut_ptr(), c"\xff".as_ptr(), value) };
+        assert_eq!(obj2.validate_utf8(), Err(CJsonError::InvalidUtf8));
+
+        json.drop();
+        obj.drop();
+        obj2.drop();
+    }
+
+    #[test]
+    fn test_ref_to_owned() {
+        let json = CJson::parse(r#"{"config":{"name":"pump","pins":[1,2]}}"#).unwrap();
+        let config = json.get_object_item("config").unwrap();
+
+        let mut copy = config.to_owned().unwrap();
+        copy.delete_item_from_object("name").unwrap();
+        copy.add_item_to_object("extra", CJson::create_true().unwrap()).unwrap();
+
+        let config = json.get_object_item("config").unwrap();
+        assert_eq!(config.get_object_item("name").unwrap().get_string_value().unwrap(), "pump");
+        assert!(config.get_object_item("extra").is_err());
+        assert!(copy.get_object_item("name").is_err());
+        assert_eq!(copy.get_object_item("pins").unwrap().get_array_size().unwrap(), 2);
+
+        let shallow = config.shallow_clone().unwrap();
+        assert!(shallow.is_object());
+        assert!(unsafe { (*shallow.as_ptr()).child.is_null() });
+
+        copy.drop();
+        shallow.drop();
+        json.drop();
+    }
+
+    #[test]
+    fn test_create_number_formatted() {
+        let price = CJson::create_number_formatted(12.3, 2).unwrap();
+        assert_eq!(price.print_unformatted().unwrap(), "12.30");
+        price.drop();
+
+        let mut obj = CJson::create_object().unwrap();
+        obj.add_item_to_object("total", CJson::create_number_formatted(0.1 + 0.2, 3).unwrap()).unwrap();
+        assert_eq!(obj.print_unformatted().unwrap(), r#"{"total":0.300}"#);
+        obj.drop();
+
+        assert_eq!(CJson::create_number_formatted(f64::NAN, 2).err(), Some(CJsonError::InvalidOperation));
+    }
+
+    #[test]
+    fn test_parse_validated() {
+        let limits = ParseLimits { max_depth: 3, max_nodes: 8, forbid_duplicate_keys: true };
+
+        let json = CJson::parse_validated(r#"{"a":[1,2],"b":{"c":null}}"#, limits).unwrap();
+        assert!(json.is_object());
+        json.drop();
+
+        assert_eq!(
+            CJson::parse_validated(r#"{"a":[[[1]]]}"#, limits).err(),
+            Some(CJsonError::DepthLimitExceeded { max: 3 })
+        );
+        assert_eq!(
+            CJson::parse_validated("[1,2,3,4,5,6,7,8]", limits).err(),
+            Some(CJsonError::NodeLimitExceeded { max: 8 })
+        );
+        assert_eq!(
+            CJson::parse_validated(r#"{"b":{"k":1,"k":2}}"#, limits).err(),
+            Some(CJsonError::DuplicateKey { key: "k".into() })
+        );
+
+        let relaxed = ParseLimits { forbid_duplicate_keys: false, ..limits };
+        let json = CJson::parse_validated(r#"{"k":1,"k":2}"#, relaxed).unwrap();
+        json.drop();
+
+        assert_eq!(CJson::parse_validated("{", limits).err(), Some(CJsonError::NullPointer));
+    }
+
+    #[test]
+    fn test_as_cstr() {
+        let json = CJson::create_string("sensor-01").unwrap();
+        let c_str = json.as_cstr().unwrap();
+        assert_eq!(c_str.to_bytes(), b"sensor-01");
+        assert_eq!(unsafe { CStr::from_ptr(cJSON_GetStringValue(json.as_ptr())) }, c_str);
+        json.drop();
+
+        let number = CJson::create_number(1.0).unwrap();
+        assert_eq!(number.as_cstr().err(), Some(CJsonError::TypeError));
+        number.drop();
+    }
+
+    #[test]
+    fn test_object_entries() {
+        let json = CJson::parse(r#"{"zone":"garden","level":3,"enabled":true}"#).unwrap();
+        let entries = json.object_entries().unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].0, "zone");
+        assert_eq!(entries[0].1.get_string_value().unwrap(), "garden");
+
+        let numbers: Vec<&str> = entries
+            .iter()
+            .filter(|(_, value)| value.is_number())
+            .map(|(key, _)| key.as_str())
+            .collect();
+        assert_eq!(numbers, ["level"]);
+
+        let array = CJson::create_array().unwrap();
+        assert_eq!(array.object_entries().err(), Some(CJsonError::TypeError));
+        array.drop();
+        json.drop();
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_reader_writer() {
+        let reader = std::io::Cursor::new(br#"{"pump":true,"flow":2.5}"#.to_vec());
+        let json = CJson::from_reader(reader).unwrap();
+        assert!(json.get_object_item("pump").unwrap().get_bool_value().unwrap());
+
+        let mut out: Vec<u8> = Vec::new();
+        json.to_writer(&mut out).unwrap();
+        assert_eq!(out, br#"{"pump":true,"flow":2.5}"#);
+        json.drop();
+
+        let err = CJson::from_reader(std::io::Cursor::new(b"{".to_vec())).err().unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        let source: &dyn std::error::Error = &CJsonError::ParseError;
+        assert_eq!(format!("{}", source), "Failed to parse JSON");
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn test_print_heapless() {
+        let json = CJson::parse(r#"{"valve":1,"open":true}"#).unwrap();
+
+        let out = json.print_heapless::<32>(false).unwrap();
+        assert_eq!(out.as_str(), r#"{"valve":1,"open":true}"#);
+
+        // Exactly as long as the output
+        let out = json.print_heapless::<23>(false).unwrap();
+        assert_eq!(out.len(), 23);
+
+        assert_eq!(
+            json.print_heapless::<22>(false).err(),
+            Some(CJsonError::CapacityExceeded { capacity: 22 })
+        );
+        assert_eq!(
+            json.print_heapless::<8>(true).err(),
+            Some(CJsonError::CapacityExceeded { capacity: 8 })
+        );
+        json.drop();
+    }
+
+    #[test]
+    fn test_array_iter() {
+        let json = CJson::parse("[1,2,3,4,5]").unwrap();
+        let even: f64 = json
+            .array_iter()
+            .unwrap()
+            .map(|item| item.get_number_value().unwrap())
+            .filter(|value| *value as i32 % 2 == 0)
+            .sum();
+        assert_eq!(even, 6.0);
+        assert_eq!(json.array_sum_f64().unwrap(), 15.0);
+        json.drop();
+
+        let mixed = CJson::parse(r#"[1,"two",3]"#).unwrap();
+        assert_eq!(mixed.array_sum_f64().err(), Some(CJsonError::TypeError));
+        mixed.drop();
+    }
+
+    #[test]
+    fn test_missing_keys() {
+        let json = CJson::parse(r#"{"ssid":"garden","Port":80}"#).unwrap();
+        let required = ["ssid", "hostname", "port"];
+
+        assert!(!json.has_all_keys(&required, false));
+        assert!(json.has_all_keys(&["ssid", "port"], false));
+        assert!(!json.has_all_keys(&["ssid", "port"], true));
+        assert_eq!(json.missing_keys(&required), ["hostname", "port"]);
+        assert!(json.missing_keys(&["ssid", "Port"]).is_empty());
+        json.drop();
+    }
+
+    #[test]
+    fn test_parse_preserve_numbers() {
+        let json = CJson::parse_preserve_numbers(r#"{"x": 1.50, "name": "v-2.0", "list": [1.0, -3e2, {"y": 0.10}]}"#).unwrap();
+        assert_eq!(
+            json.print_unformatted().unwrap(),
+            r#"{"x":1.50,"name":"v-2.0","list":[1.0,-3e2,{"y":0.10}]}"#
+        );
+        json.drop();
+
+        let top = CJson::parse_preserve_numbers("2.500").unwrap();
+        assert_eq!(top.print_unformatted().unwrap(), "2.500");
+        top.drop();
+
+        let plain = CJson::parse(r#"{"x": 1.50}"#).unwrap();
+        assert_eq!(plain.print_unformatted().unwrap(), r#"{"x":1.5}"#);
+        plain.drop();
+    }
+
+    #[test]
+    fn test_array_remove() {
+        let mut array = CJson::parse("[10,20,30]").unwrap();
+
+        let removed = array.array_remove(1).unwrap();
+        assert_eq!(removed.get_number_value().unwrap(), 20.0);
+        assert_eq!(array.print_unformatted().unwrap(), "[10,30]");
+        removed.drop();
+
+        assert_eq!(array.array_remove(2).err(), Some(CJsonError::NotFound));
+        assert_eq!(array.get_array_size().unwrap(), 2);
+        array.drop();
+    }
+
+    #[test]
+    fn test_compare_unordered() {
+        let a = CJson::parse("[1,2,3]").unwrap();
+        let b = CJson::parse("[3,2,1]").unwrap();
+        let c = CJson::parse("[1,2]").unwrap();
+        assert!(a.compare_unordered(&b, true));
+        assert!(!a.compare(&b, true));
+        assert!(!a.compare_unordered(&c, true));
+
+        let d = CJson::parse(r#"{"tags":["a","b","a"],"zones":[{"id":1},{"id":2}]}"#).unwrap();
+        let e = CJson::parse(r#"{"zones":[{"id":2},{"id":1}],"tags":["a","a","b"]}"#).unwrap();
+        let f = CJson::parse(r#"{"zones":[{"id":2},{"id":1}],"tags":["a","b","b"]}"#).unwrap();
+        assert!(d.compare_unordered(&e, true));
+        assert!(!d.compare_unordered(&f, true));
+
+        for json in [a, b, c, d, e, f] {
+            json.drop();
+        }
+    }
+
+    #[test]
+    fn test_detach_child() {
+        let mut json = CJson::parse(r#"{"pin":1,"pin":2,"mode":"out"}"#).unwrap();
+        let entries = json.object_entries().unwrap();
+        let second = entries.into_iter().nth(1).unwrap().1;
+
+        let detached = json.detach_child(second).unwrap();
+        assert_eq!(detached.get_number_value().unwrap(), 2.0);
+        assert_eq!(json.print_unformatted().unwrap(), r#"{"pin":1,"mode":"out"}"#);
+
+        // A node from another tree is rejected
+        let other = CJson::parse(r#"{"pin":3}"#).unwrap();
+        let foreign = other.get_object_item("pin").unwrap();
+        assert_eq!(json.detach_child(foreign).err(), Some(CJsonError::NotFound));
+
+        detached.drop();
+        other.drop();
+        json.drop();
+    }
+
+    #[test]
+    fn test_interior_nul() {
+        let nul = CJsonError::InteriorNul { position: 2 };
+        assert_eq!(CJson::create_string("ab\0cd").err(), Some(nul.clone()));
+        assert_eq!(CJson::parse_with_opts("\"ab\0\"", false).err(), Some(CJsonError::InteriorNul { position: 3 }));
+        assert_eq!(CJson::parse("\"ab\0\"").err(), Some(CJsonError::InteriorNul { position: 3 }));
+        assert_eq!(CJson::parse("[1,\0 2]").err(), Some(CJsonError::InteriorNul { position: 3 }));
+        assert_eq!(CJson::parse_with_length("{}\0", 2).map(|json| json.drop()), Ok(()));
+        assert_eq!(CJson::parse_with_length("{}\0", 3).err(), Some(CJsonError::InteriorNul { position: 2 }));
+
+        let mut obj = CJson::create_object().unwrap();
+        assert_eq!(obj.add_string_to_object("ab\0", "value").err(), Some(nul.clone()));
+        assert_eq!(obj.add_string_to_object("key", "ab\0").err(), Some(nul));
+        assert_eq!(obj.get_object_item("k\0").err(), Some(CJsonError::InteriorNul { position: 1 }));
+        obj.drop();
+    }
+
+    #[test]
+    fn test_set_bool_value() {
+        let mut json = CJson::parse("true").unwrap();
+        json.set_bool_value(false).unwrap();
+        assert!(json.is_false());
+        assert_eq!(json.print_unformatted().unwrap(), "false");
+        json.set_bool_value(true).unwrap();
+        assert_eq!(json.print_unformatted().unwrap(), "true");
+        json.drop();
+
+        let mut number = CJson::create_number(1.0).unwrap();
+        assert_eq!(number.set_bool_value(true).err(), Some(CJsonError::TypeError));
+        number.drop();
+    }
+
+    #[test]
+    fn test_get_array_item_rel() {
+        let json = CJson::parse("[10,20,30]").unwrap();
+        assert_eq!(json.get_array_item_rel(-1).unwrap().get_number_value().unwrap(), 30.0);
+        assert_eq!(json.get_array_item_rel(-3).unwrap().get_number_value().unwrap(), 10.0);
+        assert_eq!(json.get_array_item_rel(1).unwrap().get_number_value().unwrap(), 20.0);
+        assert_eq!(json.get_array_item_rel(-4).err(), Some(CJsonError::NotFound));
+        assert_eq!(json.get_array_item_rel(3).err(), Some(CJsonError::NotFound));
+        json.drop();
+    }
+
+    #[test]
+    fn test_raw_number_accessors() {
+        let big = CJson::create_raw("18446744073709551615").unwrap();
+        assert_eq!(big.get_u64().unwrap(), u64::MAX);
+        assert!(matches!(big.get_i64(), Err(CJsonError::NumberOutOfRange { .. })));
+        big.drop();
+
+        let float = CJson::create_number_formatted(2.5, 3).unwrap();
+        assert_eq!(float.get_number_value().unwrap(), 2.5);
+        assert_eq!(float.get_i64().unwrap(), 2);
+        float.drop();
+
+        let native = CJson::create_number(-42.0).unwrap();
+        assert_eq!(native.get_i64().unwrap(), -42);
+        assert!(matches!(native.get_u64(), Err(CJsonError::NumberOutOfRange { .. })));
+        native.drop();
+
+        let raw_text = CJson::create_raw(r#"{"a":1}"#).unwrap();
+        assert_eq!(raw_text.get_number_value().err(), Some(CJsonError::TypeError));
+        raw_text.drop();
+
+        let json = CJson::parse_preserve_numbers(r#"{"id":9007199254740993}"#).unwrap();
+        assert_eq!(json.get_object_item("id").unwrap().get_i64().unwrap(), 9_007_199_254_740_993);
+        json.drop();
+    }
+
+    #[test]
+    fn test_walk() {
+        let json = CJson::parse(r#"{"a":{"b/c":1,"m~n":[true,null]},"d":"x"}"#).unwrap();
+        let mut paths = Vec::new();
+        json.walk(|path, _| {
+            paths.push(String::from(path));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(paths, ["", "/a", "/a/b~1c", "/a/m~0n", "/a/m~0n/0", "/a/m~0n/1", "/d"]);
+
+        // Short-circuits on the first error
+        let mut visited = 0;
+        let result = json.walk(|path, _| {
+            visited += 1;
+            if path == "/a/b~1c" { Err(CJsonError::InvalidOperation) } else { Ok(()) }
+        });
+        assert_eq!(result, Err(CJsonError::InvalidOperation));
+        assert_eq!(visited, 3);
+        json.drop();
+    }
+
+    #[test]
+    fn test_parse_relaxed() {
+        let json = CJson::parse_relaxed("{\"a\":[1,2,],\"b\":3,\n}").unwrap();
+        assert_eq!(json.print_unformatted().unwrap(), r#"{"a":[1,2],"b":3}"#);
+        json.drop();
+
+        let json = CJson::parse_relaxed("{\n  // pump pin\n  \"pin\": 4 // trailing\n}").unwrap();
+        assert_eq!(json.print_unformatted().unwrap(), r#"{"pin":4}"#);
+        json.drop();
+
+        let json = CJson::parse_relaxed("[1, /* two,\n three */ 4]").unwrap();
+        assert_eq!(json.print_unformatted().unwrap(), "[1,4]");
+        json.drop();
+
+        let json = CJson::parse_relaxed(r#"{"url":"http://host/*x*/","q":"a\",]"}"#).unwrap();
+        assert_eq!(json.get_object_item("url").unwrap().get_string_value().unwrap(), "http://host/*x*/");
+        assert_eq!(json.get_object_item("q").unwrap().get_string_value().unwrap(), "a\",]");
+        json.drop();
+
+        assert!(CJson::parse_relaxed("[1,,]").is_err());
     }
 
-    /// Get number value as i32
-    pub fn get_int_value(&self) -> CJsonResult<i32> {
-        if !self.is_number() {
-            return Err(CJsonError::TypeError);
+    #[test]
+    fn test_compare_numeric_normalized() {
+        let native = CJson::parse(r#"{"x":1,"list":[2.5,"a"]}"#).unwrap();
+        let raw = CJson::parse_preserve_numbers(r#"{"list":[2.50,"a"],"x":1.0}"#).unwrap();
+        assert!(native.compare_numeric_normalized(&raw));
+        assert!(!native.compare(&raw, true));
+
+        let other = CJson::parse_preserve_numbers(r#"{"x":1.01,"list":[2.5,"a"]}"#).unwrap();
+        assert!(!native.compare_numeric_normalized(&other));
+
+        let text = CJson::parse(r#"{"x":"1","list":[2.5,"a"]}"#).unwrap();
+        assert!(!native.compare_numeric_normalized(&text));
+
+        for json in [native, raw, other, text] {
+            json.drop();
         }
-        Ok(unsafe { (*self.ptr).valueint })
     }
 
-    /// Get boolean value
-    pub fn get_bool_value(&self) -> CJsonResult<bool> {
-        if !self.is_bool() {
-            return Err(CJsonError::TypeError);
+    #[test]
+    fn test_parse_empty_input() {
+        for input in ["", "   ", "\n\t"] {
+            assert_eq!(CJson::parse(input).err(), Some(CJsonError::EmptyInput));
+            assert_eq!(CJson::parse_with_opts(input, false).err(), Some(CJsonError::EmptyInput));
         }
-        Ok(unsafe { cJSON_IsTrue(self.ptr) != 0 })
+        assert_eq!(CJson::parse_with_length("  [1]", 2).err(), Some(CJsonError::EmptyInput));
+        assert_eq!(CJson::parse("  [").err(), Some(CJsonError::NullPointer));
     }
 
-    /// Get array size
-    pub fn get_array_size(&self) -> CJsonResult<usize> {
-        if !self.is_array() {
-            return Err(CJsonError::TypeError);
+    #[test]
+    fn test_array_iter_mut_doubles_numbers() {
+        let mut json = CJson::parse("[1, 2.5, -3]").unwrap();
+        for mut item in json.array_iter_mut().unwrap() {
+            let value = item.get_number_value().unwrap();
+            item.set_number_value(value * 2.0).unwrap();
         }
-        Ok(unsafe { cJSON_GetArraySize(self.ptr) as usize })
+        assert_eq!(json.print_unformatted().unwrap(), "[2,5,-6]");
+        json.drop();
     }
 
-    /// Get array item by index
-    pub fn get_array_item(&self, index: usize) -> CJsonResult<CJsonRef> {
-        if !self.is_array() {
-            return Err(CJsonError::TypeError);
-        }
-        let ptr = unsafe { cJSON_GetArrayItem(self.ptr, index as c_int) };
-        unsafe { CJsonRef::from_ptr(ptr) }.map_err(|_| CJsonError::NotFound)
+    #[test]
+    fn test_type_name() {
+        let json = CJson::parse(r#"[null, true, false, 1.5, "s", [], {}]"#).unwrap();
+        let names: Vec<&str> = json.array_iter().unwrap().map(|item| item.type_name()).collect();
+        assert_eq!(names, ["null", "bool", "bool", "number", "string", "array", "object"]);
+        assert_eq!(json.type_name(), "array");
+        json.drop();
+
+        let raw = CJson::create_raw("{}").unwrap();
+        assert_eq!(raw.type_name(), "raw");
+        raw.drop();
+
+        let err = CJsonError::UnexpectedType { expected: "object", found: "array" };
+        assert_eq!(format!("{}", err), "Expected object, found array");
     }
 
-    /// Get object item by key
-    pub fn get_object_item(&self, key: &str) -> CJsonResult<CJsonRef> {
-        if !self.is_object() {
-            return Err(CJsonError::TypeError);
-        }
-        let c_key = CString::new(key).map_err(|_| CJsonError::InvalidUtf8)?;
-        let ptr = unsafe { cJSON_GetObjectItem(self.ptr, c_key.as_ptr()) };
-        unsafe { CJsonRef::from_ptr(ptr) }.map_err(|_| CJsonError::NotFound)
+    #[test]
+    fn test_parse_bounded() {
+        let json = r#"{"a":1}"#;
+        let parsed = CJson::parse_bounded(json, json.len()).unwrap();
+        assert!(parsed.is_object());
+        parsed.drop();
+
+        assert_eq!(
+            CJson::parse_bounded(json, json.len() - 1).err(),
+            Some(CJsonError::InputTooLarge { size: json.len(), max: json.len() - 1 })
+        );
     }
-}
 
-/// Get the cJSON library version
-#[allow(dead_code)]
-pub fn version() -> String {
-    let c_str = unsafe { cJSON_Version() };
-    unsafe { CStr::from_ptr(c_str).to_string_lossy().into_owned() }
-}
+    #[test]
+    fn test_set_item_in_object_keeps_order() {
+        let mut json = CJson::parse(r#"{"a":1,"b":2,"c":3}"#).unwrap();
+        json.set_item_in_object("b", CJson::create_string("two").unwrap()).unwrap();
+        json.set_item_in_object("d", CJson::create_number(4.0).unwrap()).unwrap();
+        assert_eq!(json.print_unformatted().unwrap(), r#"{"a":1,"b":"two","c":3,"d":4}"#);
+        json.drop();
+    }
 
-/// Get the last parse error pointer
-#[allow(dead_code)]
-pub fn get_error_ptr() -> Option<String> {
-    let c_str = unsafe { cJSON_GetErrorPtr() };
-    if c_str.is_null() {
-        None
-    } else {
-        Some(unsafe { CStr::from_ptr(c_str).to_string_lossy().into_owned() })
+    #[test]
+    fn test_print_full_precision() {
+        let value = 0.1 + 0.2;
+        let mut json = CJson::create_object().unwrap();
+        json.add_number_to_object("sum", value).unwrap();
+        json.add_number_to_object("tiny", 5e-324).unwrap();
+
+        let printed = json.print_full_precision().unwrap();
+        let parsed = CJson::parse(&printed).unwrap();
+        assert_eq!(parsed.get_object_item("sum").unwrap().get_number_value().unwrap().to_bits(), value.to_bits());
+        assert_eq!(parsed.get_object_item("tiny").unwrap().get_number_value().unwrap(), 5e-324);
+        // The original tree still holds native numbers
+        assert!(json.get_object_item("sum").unwrap().is_number());
+        parsed.drop();
+        json.drop();
     }
-}
 
-/// Minify a JSON string in place
-#[allow(dead_code)]
-pub fn minify(json: &mut String) {
-    let c_str = CString::new(json.as_str()).expect("CString conversion failed");
-    unsafe {
-        let ptr = c_str.as_ptr() as *mut c_char;
-        cJSON_Minify(ptr);
-        *json = CStr::from_ptr(ptr).to_string_lossy().into_owned();
+    #[test]
+    fn test_get_bool_value_rejects_invalid_node() {
+        let mut json = CJson::create_bool(true).unwrap();
+        unsafe { (*json.as_mut_ptr()).type_ = cJSON_Invalid };
+        assert_eq!(json.get_bool_value(), Err(CJsonError::TypeError));
+        unsafe { (*json.as_mut_ptr()).type_ = cJSON_True | cJSON_False };
+        assert_eq!(json.get_bool_value(), Err(CJsonError::TypeError));
+        let item = unsafe { CJsonRef::from_ptr(json.as_mut_ptr()) }.unwrap();
+        assert_eq!(item.get_bool_value(), Err(CJsonError::TypeError));
+
+        unsafe { (*json.as_mut_ptr()).type_ = cJSON_False };
+        assert_eq!(json.get_bool_value(), Ok(false));
+        json.drop();
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_add_raw_to_array() {
+        let mut events = CJson::create_array().unwrap();
+        events.add_raw_to_array(r#"{"event":"boot","t":1}"#).unwrap();
+        events.add_raw_to_array(r#"{"event":"wifi","t":2}"#).unwrap();
+        let printed = events.print_unformatted().unwrap();
+        assert_eq!(printed, r#"[{"event":"boot","t":1},{"event":"wifi","t":2}]"#);
+        events.drop();
+
+        let reparsed = CJson::parse(&printed).unwrap();
+        assert_eq!(reparsed.get_array_size().unwrap(), 2);
+        reparsed.drop();
+
+        let mut object = CJson::create_object().unwrap();
+        assert_eq!(object.add_raw_to_array("{}"), Err(CJsonError::TypeError));
+        object.drop();
+    }
 
     #[test]
-    fn test_parse_simple_object() {
-        let json = r#"{"name":"John","age":30}"#;
-        let parsed = CJson::parse(json).unwrap();
-        assert!(parsed.is_object());
+    fn test_object_size_and_is_empty() {
+        let empty = CJson::create_object().unwrap();
+        assert_eq!(empty.object_size(), Ok(0));
+        assert_eq!(empty.is_empty(), Ok(true));
+        empty.drop();
+
+        let json = CJson::parse(r#"{"a":1,"b":[],"c":{"d":true}}"#).unwrap();
+        assert_eq!(json.object_size(), Ok(3));
+        assert_eq!(json.is_empty(), Ok(false));
+        let nested = json.get_object_item("c").unwrap();
+        assert_eq!(nested.object_size(), Ok(1));
+        let array = json.get_object_item("b").unwrap();
+        assert_eq!(array.is_empty(), Ok(true));
+        assert_eq!(array.object_size(), Err(CJsonError::TypeError));
+        json.drop();
+
+        let array = CJson::create_array().unwrap();
+        assert_eq!(array.is_empty(), Ok(true));
+        assert_eq!(array.object_size(), Err(CJsonError::TypeError));
+        array.drop();
+
+        let number = CJson::create_number(1.0).unwrap();
+        assert_eq!(number.object_size(), Err(CJsonError::TypeError));
+        assert_eq!(number.is_empty(), Err(CJsonError::TypeError));
+        number.drop();
     }
 
     #[test]
-    fn test_parse_array() {
-        let json = r#"[1,2,3,4,5]"#;
-        let parsed = CJson::parse(json).unwrap();
-        assert!(parsed.is_array());
-        assert_eq!(parsed.get_array_size().unwrap(), 5);
+    fn test_key_bytes_round_trip() {
+        let key = [b'k', 0x80, b'y'];
+        let mut json = CJson::create_object().unwrap();
+        json.add_item_to_object_bytes(&key, CJson::create_number(1.0).unwrap()).unwrap();
+        let mut copy = json.duplicate(true).unwrap();
+        json.drop();
+
+        let member = unsafe { CJsonRef::from_ptr((*copy.as_mut_ptr()).child) }.unwrap();
+        assert_eq!(member.key_bytes(), Some(&key[..]));
+        assert_eq!(member.get_number_value(), Ok(1.0));
+        let root = unsafe { CJsonRef::from_ptr(copy.as_mut_ptr()) }.unwrap();
+        assert_eq!(root.key_bytes(), None);
+
+        // A rejected item is not consumed by cJSON
+        let item = CJson::create_null().unwrap();
+        let leftover = unsafe { item.alias() };
+        assert_eq!(copy.add_item_to_object_bytes(b"a\0b", item), Err(CJsonError::InteriorNul { position: 1 }));
+        leftover.drop();
+        copy.drop();
     }
 
     #[test]
-    fn test_create_and_get_string() {
-        let json = CJson::create_string("Hello, World!").unwrap();
-        assert!(json.is_string());
-        assert_eq!(json.get_string_value().unwrap(), "Hello, World!");
+    fn test_print_at() {
+        let json = CJson::parse(r#"{"wifi":{"ssid":"garden","channel":6},"ntp":{"server":"pool.ntp.org"}}"#).unwrap();
+        let wifi = json.print_at("/wifi", false).unwrap();
+        assert_eq!(wifi, r#"{"ssid":"garden","channel":6}"#);
+        assert!(!wifi.contains("ntp"));
+
+        let formatted = json.print_at("/wifi", true).unwrap();
+        assert!(formatted.contains("\"channel\":\t6"));
+        assert!(!formatted.contains("server"));
+
+        assert_eq!(json.print_at("/wifi/channel", false).unwrap(), "6");
+        assert_eq!(json.print_at("", false).unwrap(), json.print_unformatted().unwrap());
+        assert_eq!(json.print_at("/mqtt", false), Err(CJsonError::NotFound));
+        json.drop();
     }
 
     #[test]
-    fn test_create_and_get_number() {
-        let json = CJson::create_number(42.5).unwrap();
-        assert!(json.is_number());
-        assert_eq!(json.get_number_value().unwrap(), 42.5);
+    fn test_structurally_eq_matches_compare() {
+        let pairs = [
+            ("null", "null"),
+            ("null", "false"),
+            ("true", "true"),
+            ("1", "1.0"),
+            ("0.1", "0.10000000000000001"),
+            ("1", "2"),
+            (r#""abc""#, r#""abc""#),
+            (r#""abc""#, r#""abd""#),
+            ("[]", "[]"),
+            ("[1,2,3]", "[1,2,3]"),
+            ("[1,2,3]", "[1,2]"),
+            ("[1,2]", "[1,2,3]"),
+            ("[1,[2,[3]]]", "[1,[2,[4]]]"),
+            ("{}", "{}"),
+            (r#"{"a":1,"b":2}"#, r#"{"b":2,"a":1}"#),
+            (r#"{"a":1}"#, r#"{"a":1,"b":2}"#),
+            (r#"{"a":1,"b":2}"#, r#"{"a":1}"#),
+            (r#"{"a":1}"#, r#"{"A":1}"#),
+            (r#"{"a":1,"A":2}"#, r#"{"A":2,"a":1}"#),
+            (r#"{"x":1}"#, r#"{"x":1,"x":2}"#),
+            (r#"{"x":1,"x":2}"#, r#"{"x":1}"#),
+            (r#"{"x":1,"x":1}"#, r#"{"x":1}"#),
+            (r#"{"a":{"b":[1,{"c":null}]}}"#, r#"{"a":{"b":[1,{"c":null}]}}"#),
+            (r#"{"a":{"b":[1,{"c":null}]}}"#, r#"{"a":{"b":[1,{"c":false}]}}"#),
+            ("[{}]", "[[]]"),
+        ];
+        for (left, right) in pairs {
+            let a = CJson::parse(left).unwrap();
+            let b = CJson::parse(right).unwrap();
+            for case_sensitive in [true, false] {
+                assert_eq!(
+                    a.structurally_eq(&b, case_sensitive),
+                    a.compare(&b, case_sensitive),
+                    "{} vs {} (case sensitive: {})", left, right, case_sensitive
+                );
+            }
+            assert!(a.structurally_eq(&a, true));
+            a.drop();
+            b.drop();
+        }
+
+        let raw = CJson::create_raw("{\"k\":1}").unwrap();
+        let same = CJson::create_raw("{\"k\":1}").unwrap();
+        let string = CJson::create_string("{\"k\":1}").unwrap();
+        assert!(raw.structurally_eq(&same, true));
+        assert!(!raw.structurally_eq(&string, true));
+        raw.drop();
+        same.drop();
+        string.drop();
     }
 
     #[test]
-    fn test_create_and_get_bool() {
-        let json_true = CJson::create_true().unwrap();
-        assert!(json_true.is_true());
-        assert!(json_true.is_bool());
-        assert_eq!(json_true.get_bool_value().unwrap(), true);
+    fn test_structurally_eq_large_tree() {
+        let mut a = CJson::create_object().unwrap();
+        for i in 0..1000 {
+            let key = format!("k{}", i);
+            a.add_item_to_object(&key, CJson::create_int_array(&[i, i + 1, i + 2]).unwrap()).unwrap();
+        }
+        let mut b = a.duplicate(true).unwrap();
+        assert!(a.structurally_eq(&b, true));
 
-        let json_false = CJson::create_false().unwrap();
-        assert!(json_false.is_false());
-        assert!(json_false.is_bool());
-        assert_eq!(json_false.get_bool_value().unwrap(), false);
+        // Both stop at the first member of `a`; only the lookup of "k0" in `b` is long
+        b.delete_item_from_object("k0").unwrap();
+        b.add_item_to_object("k0", CJson::create_null().unwrap()).unwrap();
+        assert!(!a.structurally_eq(&b, true));
+        assert!(!a.compare(&b, true));
+        assert_eq!(a.structurally_eq(&b, false), a.compare(&b, false));
+        a.drop();
+        b.drop();
     }
 
     #[test]
-    fn test_create_null() {
-        let json = CJson::create_null().unwrap();
-        assert!(json.is_null());
+    fn test_drain_array() {
+        let mut queue = CJson::parse(r#"[1,"two",{"three":3},[4],null]"#).unwrap();
+        let items = queue.drain_array().unwrap();
+        assert_eq!(queue.get_array_size(), Ok(0));
+        assert_eq!(queue.print_unformatted().unwrap(), "[]");
+
+        let printed: Vec<String> = items.iter().map(|item| item.print_unformatted().unwrap()).collect();
+        assert_eq!(printed, ["1", r#""two""#, r#"{"three":3}"#, "[4]", "null"]);
+        for item in items {
+            item.drop();
+        }
+
+        assert!(queue.drain_array().unwrap().is_empty());
+        queue.drop();
+
+        let mut object = CJson::create_object().unwrap();
+        assert!(matches!(object.drain_array(), Err(CJsonError::TypeError)));
+        object.drop();
     }
 
     #[test]
-    fn test_create_object_and_add_items() {
-        let mut obj = CJson::create_object().unwrap();
-        obj.add_string_to_object("name", "Alice").unwrap();
-        obj.add_number_to_object("age", 25.0).unwrap();
-        obj.add_bool_to_object("active", true).unwrap();
+    fn test_reference_and_const_string_flags() {
+        let mut target = CJson::parse(r#"{"a":1}"#).unwrap();
+        assert!(!target.is_reference());
+        assert!(!target.is_const_string());
+
+        let reference = unsafe { CJson::from_ptr(cJSON_CreateObjectReference(target.as_mut_ptr())) }.unwrap();
+        assert!(reference.is_reference());
+        assert_eq!(reference.type_name(), "object");
+        // Deleting the reference leaves the children it points to alone
+        reference.drop();
+        assert_eq!(target.print_unformatted().unwrap(), r#"{"a":1}"#);
+
+        let mut holder = CJson::create_array().unwrap();
+        let added = unsafe { cJSON_AddItemReferenceToArray(holder.as_mut_ptr(), target.as_mut_ptr()) };
+        assert_eq!(added, 1);
+        let item = holder.get_array_item(0).unwrap();
+        assert!(item.is_reference());
+        assert!(!item.is_const_string());
+        holder.drop();
+        target.drop();
+
+        let mut object = CJson::create_object().unwrap();
+        object.add_item_to_object_cs(c"fixed", CJson::create_true().unwrap()).unwrap();
+        object.add_item_to_object("copied", CJson::create_true().unwrap()).unwrap();
+        let fixed = object.get_object_item("fixed").unwrap();
+        assert!(fixed.is_const_string());
+        assert!(!fixed.is_reference());
+        assert_eq!(fixed.type_name(), "bool");
+        assert!(!object.get_object_item("copied").unwrap().is_const_string());
+        object.drop();
+    }
 
-        assert!(obj.is_object());
-        assert!(obj.has_object_item("name"));
-        assert!(obj.has_object_item("age"));
-        assert!(obj.has_object_item("active"));
+    #[test]
+    fn test_parse_prefix() {
+        let frame = br#"{"len":4}ABCD"#;
+        let (header, offset) = CJson::parse_prefix(frame).unwrap();
+        assert_eq!(offset, 9);
+        assert_eq!(&frame[offset..], b"ABCD");
+        assert_eq!(header.get_object_item("len").unwrap().get_number_value(), Ok(4.0));
+        header.drop();
 
-        let name = obj.get_object_item("name").unwrap();
-        assert_eq!(name.get_string_value().unwrap(), "Alice");
+        let (value, offset) = CJson::parse_prefix(b"[1,2] \x00\xff").unwrap();
+        assert_eq!(offset, 6);
+        value.drop();
 
-        let age = obj.get_object_item("age").unwrap();
-        assert_eq!(age.get_number_value().unwrap(), 25.0);
+        assert!(CJson::parse_prefix(b"{\"len\":").is_err());
+        assert!(matches!(CJson::parse_prefix(b"  "), Err(CJsonError::EmptyInput)));
     }
 
     #[test]
-    fn test_create_array_and_add_items() {
-        let mut arr = CJson::create_array().unwrap();
-        arr.add_item_to_array(CJson::create_number(1.0).unwrap()).unwrap();
-        arr.add_item_to_array(CJson::create_number(2.0).unwrap()).unwrap();
-        arr.add_item_to_array(CJson::create_number(3.0).unwrap()).unwrap();
+    fn test_create_integers() {
+        for (value, printed) in [(0i64, "0"), (42, "42"), (-7, "-7"), (1 << 53, "9007199254740992")] {
+            let json = CJson::create_i64(value).unwrap();
+            assert!(json.is_number());
+            assert_eq!(json.print_unformatted().unwrap(), printed);
+            json.drop();
+        }
 
-        assert!(arr.is_array());
-        assert_eq!(arr.get_array_size().unwrap(), 3);
+        let json = CJson::create_i64(i64::MIN).unwrap();
+        assert_eq!(json.print_unformatted().unwrap(), "-9223372036854775808");
+        assert_eq!(json.get_i64(), Ok(i64::MIN));
+        json.drop();
 
-        let item = arr.get_array_item(1).unwrap();
-        assert_eq!(item.get_number_value().unwrap(), 2.0);
+        let json = CJson::create_u64(u64::MAX).unwrap();
+        assert_eq!(json.print_unformatted().unwrap(), "18446744073709551615");
+        assert_eq!(json.get_u64(), Ok(u64::MAX));
+        json.drop();
+
+        let json = CJson::create_u64((1 << 53) + 1).unwrap();
+        assert_eq!(json.print_unformatted().unwrap(), "9007199254740993");
+        json.drop();
     }
 
     #[test]
-    fn test_print_formatted() {
-        let mut obj = CJson::create_object().unwrap();
-        obj.add_string_to_object("key", "value").unwrap();
-        
-        let json_str = obj.print().unwrap();
-        assert!(json_str.contains("key"));
-        assert!(json_str.contains("value"));
+    fn test_print_borrowed_items() {
+        let json = CJson::parse(r#"[{"name":"pump","on":true},[1,2],"x"]"#).unwrap();
+        let items: Vec<CJsonRef> = json.array_iter().unwrap().collect();
+
+        assert_eq!(items[0].print_unformatted().unwrap(), r#"{"name":"pump","on":true}"#);
+        assert_eq!(items[0].print().unwrap(), "{\n\t\"name\":\t\"pump\",\n\t\"on\":\ttrue\n}");
+        assert_eq!(items[1].print_unformatted().unwrap(), "[1,2]");
+        assert_eq!(items[1].print().unwrap(), "[1, 2]");
+        assert_eq!(format!("{}", items[1]), "[1,2]");
+        assert_eq!(format!("{}", items[2]), r#""x""#);
+        assert!(format!("{:?}", items[0]).starts_with("CJsonRef"));
+
+        // Printing a member does not include its key
+        let name = items[0].get_object_item("name").unwrap();
+        assert_eq!(name.to_string(), r#""pump""#);
+        json.drop();
     }
 
     #[test]
-    fn test_print_unformatted() {
-        let mut obj = CJson::create_object().unwrap();
-        obj.add_string_to_object("key", "value").unwrap();
-        
-        let json_str = obj.print_unformatted().unwrap();
-        assert!(json_str.contains("key"));
-        assert!(json_str.contains("value"));
-        assert!(!json_str.contains("\n")); // No newlines in unformatted
+    fn test_raw_pointer_round_trip() {
+        let json = CJson::parse(r#"{"pump":{"on":true}}"#).unwrap();
+        let raw = json.into_raw();
+
+        let member = unsafe { cJSON_GetObjectItemCaseSensitive(raw, c"pump".as_ptr()) };
+        let borrowed = unsafe { CJsonRef::from_borrowed_ptr(member) }.unwrap();
+        assert_eq!(borrowed.print_unformatted().unwrap(), r#"{"on":true}"#);
+
+        let json = unsafe { CJson::from_owned_ptr(raw) }.unwrap();
+        assert_eq!(json.print_unformatted().unwrap(), r#"{"pump":{"on":true}}"#);
+        json.drop();
+
+        assert!(matches!(unsafe { CJson::from_owned_ptr(ptr::null_mut()) }, Err(CJsonError::NullPointer)));
+        assert!(matches!(unsafe { CJsonRef::from_borrowed_ptr(ptr::null_mut()) }, Err(CJsonError::NullPointer)));
     }
 
     #[test]
-    fn test_duplicate() {
-        let original = CJson::create_string("test").unwrap();
-        let duplicate = original.duplicate(true).unwrap();
-        
-        assert_eq!(
-            original.get_string_value().unwrap(),
-            duplicate.get_string_value().unwrap()
-        );
+    fn test_get_int_value_range() {
+        let json = CJson::parse(r#"[42,-7,2.9,2147483647,-2147483648,3000000000,-3000000000,"1"]"#).unwrap();
+        let values: Vec<CJsonResult<i32>> = json.array_iter().unwrap().map(|item| item.get_int_value()).collect();
+        assert_eq!(values[0], Ok(42));
+        assert_eq!(values[1], Ok(-7));
+        assert_eq!(values[2], Ok(2));
+        assert_eq!(values[3], Ok(i32::MAX));
+        assert_eq!(values[4], Ok(i32::MIN));
+        assert_eq!(values[5], Err(CJsonError::NumberOutOfRange {
+            value: 3_000_000_000.0,
+            min: i32::MIN as f64,
+            max: i32::MAX as f64,
+        }));
+        assert!(matches!(values[6], Err(CJsonError::NumberOutOfRange { .. })));
+        assert_eq!(values[7], Err(CJsonError::TypeError));
+
+        let big = json.get_array_item(5).unwrap();
+        assert_eq!(big.get_i64(), Ok(3_000_000_000));
+        json.drop();
+
+        let owned = CJson::create_number(3_000_000_000.0).unwrap();
+        assert!(matches!(owned.get_int_value(), Err(CJsonError::NumberOutOfRange { .. })));
+        owned.drop();
     }
 
     #[test]
-    fn test_compare() {
-        let json1 = CJson::create_number(42.0).unwrap();
-        let json2 = CJson::create_number(42.0).unwrap();
-        let json3 = CJson::create_number(43.0).unwrap();
+    fn test_into_string() {
+        let mut json = CJson::create_object().unwrap();
+        json.add_string_to_object("name", "pump").unwrap();
+        json.add_number_to_object("flow", 2.5).unwrap();
+        assert_eq!(json.into_string(true).unwrap(), "{\n\t\"name\":\t\"pump\",\n\t\"flow\":\t2.5\n}");
 
-        assert!(json1.compare(&json2, true));
-        assert!(!json1.compare(&json3, true));
+        let json = CJson::parse("[1, 2]").unwrap();
+        assert_eq!(json.into_string(false).unwrap(), "[1,2]");
     }
 
     #[test]
-    fn test_create_int_array() {
-        let values = [1, 2, 3, 4, 5];
-        let arr = CJson::create_int_array(&values).unwrap();
-        
-        assert!(arr.is_array());
-        assert_eq!(arr.get_array_size().unwrap(), 5);
+    fn test_object_from_pairs() {
+        let object = CJson::object_from_pairs(&[
+            ("name", CJson::create_string("pump").unwrap()),
+            ("flow", CJson::create_number(2.5).unwrap()),
+            ("zones", CJson::create_int_array(&[1, 2]).unwrap()),
+        ])
+        .unwrap();
+        let expected = CJson::parse(r#"{"name":"pump","flow":2.5,"zones":[1,2]}"#).unwrap();
+        assert!(object.compare(&expected, true));
+        assert_eq!(object.print_unformatted().unwrap(), expected.print_unformatted().unwrap());
+        object.drop();
+        expected.drop();
+
+        let pairs = [("a", CJson::create_true().unwrap()), ("a", CJson::create_false().unwrap())];
+        let result = CJson::object_from_pairs(&pairs);
+        assert!(matches!(result, Err(CJsonError::DuplicateKey { ref key }) if key == "a"));
+        // Nothing was moved, the values are still the caller's
+        for (_, value) in pairs {
+            value.drop();
+        }
     }
 
     #[test]
-    fn test_create_double_array() {
-        let values = [1.1, 2.2, 3.3];
-        let arr = CJson::create_double_array(&values).unwrap();
-        
-        assert!(arr.is_array());
-        assert_eq!(arr.get_array_size().unwrap(), 3);
+    fn test_object_from_pairs_failed_key_copy_returns_values() {
+        let ((), stats) = crate::alloc_counter::measure(|| {
+            let pairs = [("a", CJson::create_number(1.0).unwrap()), ("b", CJson::create_number(2.0).unwrap())];
+            // The object and the copy of "a" succeed, the copy of "b" fails
+            let result = crate::alloc_counter::fail_c_alloc_after(2, || CJson::object_from_pairs(&pairs));
+            assert_eq!(result.err(), Some(CJsonError::AllocationError));
+            for (_, value) in pairs {
+                value.drop();
+            }
+        });
+        assert_eq!(stats.c_live, 0);
     }
 
     #[test]
-    #[ignore] // Temporarily disabled due to potential double free issue
-    fn test_create_string_array() {
-        let values = ["foo", "bar", "baz"];
-        let arr = CJson::create_string_array(&values).unwrap();
-        
-        assert!(arr.is_array());
-        assert_eq!(arr.get_array_size().unwrap(), 3);
+    fn test_array_union() {
+        let a = CJson::parse("[1,2]").unwrap();
+        let b = CJson::parse("[2,3]").unwrap();
+        let union = a.array_union(&b, true).unwrap();
+        assert_eq!(union.print_unformatted().unwrap(), "[1,2,3]");
+        union.drop();
+        // The inputs are left untouched
+        assert_eq!(b.print_unformatted().unwrap(), "[2,3]");
+        a.drop();
+        b.drop();
+
+        let a = CJson::parse(r#"[{"Host":"a"},1,1]"#).unwrap();
+        let b = CJson::parse(r#"[{"host":"a"},4,4,{"Host":"a"}]"#).unwrap();
+        let union = a.array_union(&b, true).unwrap();
+        assert_eq!(union.print_unformatted().unwrap(), r#"[{"Host":"a"},1,1,{"host":"a"},4]"#);
+        union.drop();
+        let union = a.array_union(&b, false).unwrap();
+        assert_eq!(union.print_unformatted().unwrap(), r#"[{"Host":"a"},1,1,4]"#);
+        union.drop();
+
+        let object = CJson::create_object().unwrap();
+        assert!(matches!(a.array_union(&object, true), Err(CJsonError::TypeError)));
+        object.drop();
+        a.drop();
+        b.drop();
     }
 
     #[test]
-    fn test_delete_item_from_array() {
-        let mut arr = CJson::create_array().unwrap();
-        arr.add_item_to_array(CJson::create_number(1.0).unwrap()).unwrap();
-        arr.add_item_to_array(CJson::create_number(2.0).unwrap()).unwrap();
-        arr.add_item_to_array(CJson::create_number(3.0).unwrap()).unwrap();
-
-        assert_eq!(arr.get_array_size().unwrap(), 3);
-        arr.delete_item_from_array(1).unwrap();
-        assert_eq!(arr.get_array_size().unwrap(), 2);
+    fn test_parse_error_position() {
+        let source = "{\n  \"a\": x,\n  \"b\": 2\n}";
+        let result = CJson::parse_prefix_located(source.as_bytes());
+        assert_eq!(result.err(), Some(CJsonError::ParseErrorAt { offset: 9, line: 2, column: 8 }));
+        assert_eq!(&source[9..10], "x");
+        // The location is opt-in, plain parse_prefix keeps its error
+        assert_eq!(CJson::parse_prefix(source.as_bytes()).err(), Some(CJsonError::NullPointer));
+        let (value, offset) = CJson::parse_prefix_located(b"[1] tail").unwrap();
+        assert_eq!(offset, 4);
+        value.drop();
+
+        assert_eq!(offset_to_line_col("", 0), (1, 1));
+        assert_eq!(offset_to_line_col("ab\ncd", 2), (1, 3));
+        assert_eq!(offset_to_line_col("ab\ncd", 3), (2, 1));
+        assert_eq!(offset_to_line_col("ab\ncd", 100), (2, 3));
+        // "é" is two bytes but one column
+        assert_eq!(offset_to_line_col("\"é\":", 4), (1, 4));
+        assert_eq!(offset_to_line_col("é", 1), (1, 1));
     }
 
     #[test]
-    fn test_delete_item_from_object() {
-        let mut obj = CJson::create_object().unwrap();
-        obj.add_string_to_object("key1", "value1").unwrap();
-        obj.add_string_to_object("key2", "value2").unwrap();
+    fn test_to_display_string() {
+        let json = CJson::parse(
+            r#"{"name":"garden \"north\"","level":42,"ratio":0.5,"on":true,"off":false,"none":null,"list":[1],"obj":{}}"#,
+        )
+        .unwrap();
+        let display = |key: &str| json.get_object_item(key).unwrap().to_display_string();
+
+        assert_eq!(display("name"), Ok(String::from("garden \"north\"")));
+        assert_eq!(display("level"), Ok(String::from("42")));
+        assert_eq!(display("ratio"), Ok(String::from("0.5")));
+        assert_eq!(display("on"), Ok(String::from("true")));
+        assert_eq!(display("off"), Ok(String::from("false")));
+        assert_eq!(display("none"), Ok(String::from("null")));
+        assert_eq!(display("list"), Err(CJsonError::TypeError));
+        assert_eq!(display("obj"), Err(CJsonError::TypeError));
+
+        let raw = CJson::create_raw("12345678901234567890").unwrap();
+        assert_eq!(raw.to_display_string(), Ok(String::from("12345678901234567890")));
+        let text = CJson::create_string("plain").unwrap();
+        assert_eq!(text.to_display_string(), Ok(String::from("plain")));
+        assert_eq!(text.print_unformatted(), Ok(String::from("\"plain\"")));
+
+        raw.drop();
+        text.drop();
+        json.drop();
+    }
 
-        assert!(obj.has_object_item("key1"));
-        obj.delete_item_from_object("key1").unwrap();
-        assert!(!obj.has_object_item("key1"));
-        assert!(obj.has_object_item("key2"));
+    #[test]
+    fn test_try_from_node() {
+        let json = CJson::parse(r#"{"ratio":0.25,"count":-42,"on":true,"name":"garden"}"#).unwrap();
+
+        let ratio: f64 = (&json.get_object_item("ratio").unwrap()).try_into().unwrap();
+        let count: i64 = (&json.get_object_item("count").unwrap()).try_into().unwrap();
+        let on: bool = (&json.get_object_item("on").unwrap()).try_into().unwrap();
+        let name: String = (&json.get_object_item("name").unwrap()).try_into().unwrap();
+        assert_eq!(ratio, 0.25);
+        assert_eq!(count, -42);
+        assert!(on);
+        assert_eq!(name, "garden");
+
+        let owned = CJson::create_number(7.0).unwrap();
+        assert_eq!(f64::try_from(&owned), Ok(7.0));
+        assert_eq!(i64::try_from(&owned), Ok(7));
+        assert_eq!(bool::try_from(&owned), Err(CJsonError::TypeError));
+        assert_eq!(String::try_from(&owned), Err(CJsonError::TypeError));
+        let text = CJson::create_string("7").unwrap();
+        assert_eq!(f64::try_from(&text), Err(CJsonError::TypeError));
+        assert_eq!(String::try_from(&text), Ok(String::from("7")));
+
+        let name = json.get_object_item("name").unwrap();
+        assert_eq!(i64::try_from(&name), Err(CJsonError::TypeError));
+
+        owned.drop();
+        text.drop();
+        json.drop();
     }
 
     #[test]
-    fn test_parse_nested_object() {
-        let json = r#"{"person":{"name":"John","age":30}}"#;
-        let parsed = CJson::parse(json).unwrap();
-        
-        let person = parsed.get_object_item("person").unwrap();
-        assert!(person.is_object());
-        
-        let name = person.get_object_item("name").unwrap();
-        assert_eq!(name.get_string_value().unwrap(), "John");
+    fn test_debug_summary() {
+        let values: Vec<i32> = (0..100).collect();
+        let array = CJson::create_int_array(&values).unwrap();
+        let summary = array.debug_summary(3, 16);
+        assert_eq!(summary, "[0,1,2,… (97 more)]");
+        assert!(format!("{:?}", array).len() < 64);
+        array.drop();
+
+        let long = "x".repeat(1000);
+        let json = CJson::parse(&format!(r#"{{"name":"{}","ok":true,"none":null,"nested":{{"a":[1.5]}}}}"#, long)).unwrap();
+        let summary = json.debug_summary(8, 4);
+        assert_eq!(summary, r#"{"name":"xxxx…","ok":true,"none":null,"nest…":{"a":[1.5]}}"#);
+        assert_eq!(json.debug_summary(1, 64), r#"{"name":"xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx…",… (3 more)}"#);
+        assert_eq!(json.debug_summary(0, 4), "{… (4 more)}");
+        json.drop();
     }
 
     #[test]
-    fn test_type_error() {
-        let json = CJson::create_string("not a number").unwrap();
-        assert!(json.get_number_value().is_err());
+    fn test_build_index() {
+        let mut object = CJson::create_object().unwrap();
+        for i in 0..200 {
+            object.add_item_to_object(&format!("key{}", i), CJson::create_number(i as f64).unwrap()).unwrap();
+        }
+        object.add_item_to_object("key7", CJson::create_null().unwrap()).unwrap();
+
+        let index = object.build_index();
+        assert_eq!(index.len(), 200);
+
+        // Same members as the naive scan
+        for i in 0..200 {
+            let key = format!("key{}", i);
+            let indexed = index.get(&key).unwrap();
+            let naive = object.get_object_item(&key).unwrap();
+            assert_eq!(indexed.as_ptr(), naive.as_ptr());
+            assert_eq!(indexed.get_number_value(), Ok(i as f64));
+        }
+
+        // First duplicate wins, like cJSON_GetObjectItemCaseSensitive
+        assert!(index.get("key7").unwrap().is_number());
+        assert!(index.contains_key("key199"));
+        assert_eq!(index.get("KEY1").err(), Some(CJsonError::NotFound));
+        assert_eq!(index.get("missing").err(), Some(CJsonError::NotFound));
+        object.drop();
+
+        let array = CJson::create_array().unwrap();
+        assert!(array.build_index().is_empty());
+        array.drop();
     }
 
     #[test]
-    fn test_not_found_error() {
-        let obj = CJson::create_object().unwrap();
-        assert!(obj.get_object_item("nonexistent").is_err());
+    fn test_array_sum_does_not_allocate() {
+        let count = 100_000;
+        let mut text = String::from("[");
+        for i in 0..count {
+            if i > 0 {
+                text.push(',');
+            }
+            text.push_str(&format!("{}", i));
+        }
+        text.push(']');
+
+        let json = CJson::parse(&text).unwrap();
+        let ((sum, max), stats) = crate::alloc_counter::measure(|| {
+            let sum = json.array_sum_f64().unwrap();
+            let max = json
+                .array_iter()
+                .unwrap()
+                .filter_map(|item| item.get_number_value().ok())
+                .fold(f64::MIN, f64::max);
+            (sum, max)
+        });
+
+        assert_eq!(stats.rust_allocs, 0, "Reduction allocated on the heap");
+        assert_eq!(stats.c_allocs, 0, "Reduction allocated through cJSON");
+        assert_eq!(sum, (count as f64 - 1.0) * count as f64 / 2.0);
+        assert_eq!(max, count as f64 - 1.0);
+        json.drop();
     }
 
     #[test]
-    fn test_parse_with_length() {
-        let json = r#"{"key":"value"}"#;
-        let parsed = CJson::parse_with_length(json, json.len()).unwrap();
-        assert!(parsed.is_object());
+    fn test_parse_does_not_copy_input() {
+        let mut text = String::from("{\"samples\":[");
+        let mut i = 0;
+        while text.len() < 1024 * 1024 {
+            if i > 0 {
+                text.push(',');
+            }
+            text.push_str(&format!("{{\"id\":{},\"zone\":\"garden\"}}", i));
+            i += 1;
+        }
+        text.push_str("]}");
+
+        let (json, parse) = crate::alloc_counter::measure(|| CJson::parse(&text));
+        json.unwrap().drop();
+
+        // The opt-in NUL-checking path still builds a CString copy
+        let (json, opts) = crate::alloc_counter::measure(|| CJson::parse_with_opts(&text, false));
+        json.unwrap().drop();
+
+        assert_eq!(parse.rust_bytes, 0, "parse allocated {} bytes", parse.rust_bytes);
+        assert!(opts.rust_bytes > text.len());
     }
 
     #[test]
-    fn test_case_sensitive_get() {
-        let mut obj = CJson::create_object().unwrap();
-        obj.add_string_to_object("Key", "value").unwrap();
-        
-        assert!(obj.get_object_item_case_sensitive("Key").is_ok());
-        assert!(obj.get_object_item_case_sensitive("key").is_err());
+    fn test_ref_object_entries() {
+        let json = CJson::parse(r#"{"wifi":{"ssid":"garden","channel":6}}"#).unwrap();
+        let wifi = json.get_object_item("wifi").unwrap();
+        let entries = wifi.object_entries().unwrap();
+        let keys: Vec<&str> = entries.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, ["ssid", "channel"]);
+        assert_eq!(entries[1].1.get_number_value(), Ok(6.0));
+        assert_eq!(entries[0].1.object_entries().err(), Some(CJsonError::TypeError));
+        json.drop();
     }
 }
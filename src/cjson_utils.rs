@@ -27,10 +27,12 @@ extern crate alloc;
 
 use alloc::ffi::CString;
 use alloc::string::String;
-use core::ffi::{CStr, c_char};
+use alloc::vec::Vec;
+use core::ffi::c_char;
+use core::ptr;
 
-use crate::cjson::{CJson, CJsonError, CJsonResult};
-use crate::cjson_ffi::cJSON;
+use crate::cjson::{CJson, CJsonError, CJsonResult, CJsonType, COwnedStr};
+use crate::cjson_ffi::{cJSON, cJSON_Compare, cJSON_Duplicate, cJSON_ReplaceItemViaPointer, cJSON_StringIsConst};
 use crate::cjson_utils_ffi::*;
 
 /// JSON Pointer utilities (RFC6901)
@@ -46,7 +48,7 @@ impl JsonPointer {
     /// # Returns
     /// A borrowed reference to the found item, or NotFound error
     pub fn get(object: &CJson, pointer: &str) -> CJsonResult<CJsonRef> {
-        let c_pointer = CString::new(pointer).map_err(|_| CJsonError::InvalidUtf8)?;
+        let c_pointer = CString::new(pointer)?;
         let ptr = unsafe {
             cJSONUtils_GetPointer(object.as_ptr() as *mut cJSON, c_pointer.as_ptr() as *const i8)
         };
@@ -62,7 +64,7 @@ impl JsonPointer {
     /// # Returns
     /// A borrowed reference to the found item, or NotFound error
     pub fn get_case_sensitive(object: &CJson, pointer: &str) -> CJsonResult<CJsonRef> {
-        let c_pointer = CString::new(pointer).map_err(|_| CJsonError::InvalidUtf8)?;
+        let c_pointer = CString::new(pointer)?;
         let ptr = unsafe {
             cJSONUtils_GetPointerCaseSensitive(
                 object.as_ptr() as *mut cJSON,
@@ -72,6 +74,61 @@ impl JsonPointer {
         unsafe { CJsonRef::from_ptr(ptr) }.map_err(|_| CJsonError::NotFound)
     }
 
+    /// Replace the value at a JSON Pointer (case-sensitive), keeping its key
+    /// and position; the old value is freed.
+    ///
+    /// # Arguments
+    /// * `object` - The JSON document to modify
+    /// * `pointer` - The JSON Pointer of the value to replace ("" replaces the whole document)
+    /// * `value` - The new value; ownership moves into the document (it is freed on error)
+    ///
+    /// # Returns
+    /// Ok(()) on success, or NotFound if nothing exists at `pointer` or it
+    /// does not start with `/`
+    pub fn replace(object: &mut CJson, pointer: &str, value: CJson) -> CJsonResult<()> {
+        if pointer.is_empty() {
+            core::mem::replace(object, value).drop();
+            return Ok(());
+        }
+        // cJSONUtils resolves such a pointer to the root itself, which would
+        // then be replaced (and freed) inside itself
+        if !pointer.starts_with('/') {
+            value.drop();
+            return Err(CJsonError::NotFound);
+        }
+        let parent_pointer = &pointer[..pointer.rfind('/').unwrap_or(0)];
+        let target = Self::get_case_sensitive(object, pointer)
+            .and_then(|target| Ok((Self::get_case_sensitive(object, parent_pointer)?, target)));
+        let (parent, target) = match target {
+            Ok(found) => found,
+            Err(error) => {
+                value.drop();
+                return Err(error);
+            }
+        };
+        let parent = parent.as_ptr() as *mut cJSON;
+        let target = target.as_ptr() as *mut cJSON;
+        let raw = value.into_raw();
+        let result = unsafe {
+            // Hand the key over so the replacement keeps its name
+            (*raw).string = (*target).string;
+            (*raw).type_ |= (*target).type_ & cJSON_StringIsConst;
+            (*target).string = ptr::null_mut();
+            cJSON_ReplaceItemViaPointer(parent, target, raw)
+        };
+        if result != 0 {
+            return Ok(());
+        }
+        // Give the key back to the item that stays and free the unused value
+        unsafe {
+            (*target).string = (*raw).string;
+            (*raw).string = ptr::null_mut();
+            (*raw).type_ &= !cJSON_StringIsConst;
+            CJson::from_ptr(raw)?.drop();
+        }
+        Err(CJsonError::InvalidOperation)
+    }
+
     /// Find a JSON Pointer path from one object to a target value within it.
     /// 
     /// # Arguments
@@ -81,15 +138,127 @@ impl JsonPointer {
     /// # Returns
     /// The JSON Pointer path as a String, or NotFound error
     pub fn find_from_object_to(object: &CJson, target: &CJson) -> CJsonResult<String> {
-        let ptr = unsafe {
-            cJSONUtils_FindPointerFromObjectTo(object.as_ptr(), target.as_ptr())
+        let path = unsafe {
+            COwnedStr::new(cJSONUtils_FindPointerFromObjectTo(object.as_ptr(), target.as_ptr()) as *mut c_char)
+        }
+        .ok_or(CJsonError::NotFound)?;
+        Ok(path.to_string_lossy())
+    }
+
+    /// Find the JSON Pointer of every value structurally equal to `value`.
+    ///
+    /// # Arguments
+    /// * `object` - The JSON object to search in
+    /// * `value` - The value to look for, compared like `CJson::compare`
+    /// * `case_sensitive` - Whether object keys inside `value` are compared case sensitively
+    ///
+    /// # Returns
+    /// The pointers in document (pre-order) order, empty if nothing matches,
+    /// or InvalidUtf8 if a key on the way cannot be written as a pointer.
+    pub fn find_all(object: &CJson, value: &CJson, case_sensitive: bool) -> CJsonResult<Vec<String>> {
+        let mut found = Vec::new();
+        object.walk(|path, node| {
+            if node.structurally_eq(value, case_sensitive) {
+                found.push(String::from(path));
+            }
+            Ok(())
+        })?;
+        Ok(found)
+    }
+}
+
+/// A single JSON Patch (RFC6902) operation
+///
+/// The operation owns its `value`, which is freed when the operation is dropped.
+#[derive(Debug)]
+pub enum PatchOp {
+    /// Add `value` at `path`
+    Add { path: String, value: CJson },
+    /// Remove the value at `path`
+    Remove { path: String },
+    /// Replace the value at `path` with `value`
+    Replace { path: String, value: CJson },
+    /// Move the value at `from` to `path`
+    Move { from: String, path: String },
+    /// Copy the value at `from` to `path`
+    Copy { from: String, path: String },
+    /// Check that the value at `path` equals `value`
+    Test { path: String, value: CJson },
+}
+
+impl PatchOp {
+    /// Get the RFC6902 operation name ("add", "remove", ...)
+    pub fn op_name(&self) -> &'static str {
+        match self {
+            PatchOp::Add { .. } => "add",
+            PatchOp::Remove { .. } => "remove",
+            PatchOp::Replace { .. } => "replace",
+            PatchOp::Move { .. } => "move",
+            PatchOp::Copy { .. } => "copy",
+            PatchOp::Test { .. } => "test",
+        }
+    }
+
+    /// Parse an operation object like `{"op":"add","path":"/a","value":1}`.
+    /// 
+    /// The value, if any, is deep-copied out of `node`.
+    /// 
+    /// # Returns
+    /// The typed operation, or TypeError/NotFound for a malformed operation
+    pub fn from_ref(node: &CJsonRef) -> CJsonResult<Self> {
+        let op = node.get_object_item("op")?.get_string_value()?;
+        let path = node.get_object_item("path")?.get_string_value()?;
+        let value = || -> CJsonResult<CJson> {
+            let value = node.get_object_item("value")?;
+            unsafe { CJson::from_ptr(cJSON_Duplicate(value.as_ptr(), 1)) }
         };
-        if ptr.is_null() {
-            return Err(CJsonError::NotFound);
+        let from = || node.get_object_item("from")?.get_string_value();
+
+        match op.as_str() {
+            "add" => Ok(PatchOp::Add { path, value: value()? }),
+            "remove" => Ok(PatchOp::Remove { path }),
+            "replace" => Ok(PatchOp::Replace { path, value: value()? }),
+            "move" => Ok(PatchOp::Move { from: from()?, path }),
+            "copy" => Ok(PatchOp::Copy { from: from()?, path }),
+            "test" => Ok(PatchOp::Test { path, value: value()? }),
+            _ => Err(CJsonError::TypeError),
+        }
+    }
+
+    /// Build the JSON object for this operation.
+    /// 
+    /// The value, if any, is deep-copied so the operation keeps its own.
+    /// 
+    /// # Returns
+    /// A new CJson object like `{"op":"add","path":"/a","value":1}`
+    pub fn to_cjson(&self) -> CJsonResult<CJson> {
+        let mut obj = CJson::create_object()?;
+        obj.add_string_to_object("op", self.op_name())?;
+        match self {
+            PatchOp::Add { path, value }
+            | PatchOp::Replace { path, value }
+            | PatchOp::Test { path, value } => {
+                obj.add_string_to_object("path", path)?;
+                obj.add_item_to_object("value", value.duplicate(true)?)?;
+            }
+            PatchOp::Remove { path } => {
+                obj.add_string_to_object("path", path)?;
+            }
+            PatchOp::Move { from, path } | PatchOp::Copy { from, path } => {
+                obj.add_string_to_object("from", from)?;
+                obj.add_string_to_object("path", path)?;
+            }
+        }
+        Ok(obj)
+    }
+}
+
+impl Drop for PatchOp {
+    fn drop(&mut self) {
+        match self {
+            PatchOp::Add { value, .. } | PatchOp::Replace { value, .. } | PatchOp::Test { value, .. } => value.drop(),
+            PatchOp::Remove { .. } | PatchOp::Move { .. } | PatchOp::Copy { .. } => {}
         }
-        let path = unsafe { CStr::from_ptr(ptr as *const c_char).to_string_lossy().into_owned() };
-        unsafe { crate::cjson_ffi::cJSON_free(ptr as *mut core::ffi::c_void) };
-        Ok(path)
     }
 }
 
@@ -131,6 +300,44 @@ impl JsonPatch {
         unsafe { CJson::from_ptr(ptr) }
     }
 
+    /// Compute the typed JSON Patch (RFC6902) operations that transform 'from' into 'to'.
+    /// 
+    /// Unlike `generate`, the inputs are left untouched: the patch is generated
+    /// from copies, so the caller's key order is preserved.
+    /// 
+    /// # Arguments
+    /// * `from` - The original JSON object
+    /// * `to` - The target JSON object
+    /// 
+    /// # Returns
+    /// The list of operations, in the order they must be applied. Each
+    /// operation owns a copy of its value, freed when the operation is dropped.
+    pub fn diff(from: &CJson, to: &CJson) -> CJsonResult<Vec<PatchOp>> {
+        let patches = Self::diff_json(from, to)?;
+
+        let ret = patches.get_array_size().and_then(|size| {
+            (0..size)
+                .map(|i| patches.get_array_item(i).and_then(|node| PatchOp::from_ref(&node)))
+                .collect::<CJsonResult<Vec<PatchOp>>>()
+        });
+        patches.drop();
+        ret
+    }
+
+    /// Generate a JSON Patch (RFC6902) to transform 'from' into 'to' without modifying them.
+    /// 
+    /// Same as `generate`, but runs on copies so the caller's key order is preserved.
+    /// 
+    /// # Arguments
+    /// * `from` - The original JSON object
+    /// * `to` - The target JSON object
+    /// 
+    /// # Returns
+    /// A new CJson object containing the patch operations
+    pub fn diff_json(from: &CJson, to: &CJson) -> CJsonResult<CJson> {
+        generate_on_copies(from, to, Self::generate)
+    }
+
     /// Apply a JSON Patch (RFC6902) to an object.
     /// 
     /// # Arguments
@@ -169,6 +376,42 @@ impl JsonPatch {
         }
     }
 
+    /// Apply a single typed patch operation to an object.
+    /// 
+    /// Useful to apply a patch one step at a time and know which step failed.
+    /// 
+    /// # Arguments
+    /// * `object` - The JSON object to patch
+    /// * `op` - The operation to apply
+    /// 
+    /// # Returns
+    /// Ok(()) on success, or InvalidOperation if the operation failed
+    pub fn apply_op(object: &mut CJson, op: &PatchOp) -> CJsonResult<()> {
+        let mut patches = CJson::create_array()?;
+        let ret = op
+            .to_cjson()
+            .and_then(|item| patches.add_item_to_array(item))
+            .and_then(|_| Self::apply(object, &patches));
+        patches.drop();
+        ret
+    }
+
+    /// Evaluate a "test" operation without modifying anything.
+    /// 
+    /// # Arguments
+    /// * `object` - The JSON object to check
+    /// * `path` - The JSON Pointer path of the value to test
+    /// * `value` - The expected value
+    /// 
+    /// # Returns
+    /// true if the value at `path` equals `value`, false on mismatch or missing path
+    pub fn test(object: &CJson, path: &str, value: &CJson) -> bool {
+        match JsonPointer::get_case_sensitive(object, path) {
+            Ok(node) => unsafe { cJSON_Compare(node.as_ptr(), value.as_ptr(), 1) != 0 },
+            Err(_) => false,
+        }
+    }
+
     /// Add a patch operation to a patches array.
     /// 
     /// # Arguments
@@ -186,8 +429,8 @@ impl JsonPatch {
             return Err(CJsonError::TypeError);
         }
 
-        let c_operation = CString::new(operation).map_err(|_| CJsonError::InvalidUtf8)?;
-        let c_path = CString::new(path).map_err(|_| CJsonError::InvalidUtf8)?;
+        let c_operation = CString::new(operation)?;
+        let c_path = CString::new(path)?;
 
         let value_ptr = value.map(|v| v.as_ptr()).unwrap_or(core::ptr::null());
 
@@ -201,6 +444,33 @@ impl JsonPatch {
         }
         Ok(())
     }
+
+    /// Add a "move" operation to a patches array.
+    /// 
+    /// # Arguments
+    /// * `array` - The array of patch operations
+    /// * `from` - The JSON Pointer path of the value to move
+    /// * `path` - The JSON Pointer path to move the value to
+    pub fn add_move(array: &mut CJson, from: &str, path: &str) -> CJsonResult<()> {
+        Self::add_op_to_array(array, &PatchOp::Move { from: String::from(from), path: String::from(path) })
+    }
+
+    /// Add a "copy" operation to a patches array.
+    /// 
+    /// # Arguments
+    /// * `array` - The array of patch operations
+    /// * `from` - The JSON Pointer path of the value to copy
+    /// * `path` - The JSON Pointer path to copy the value to
+    pub fn add_copy(array: &mut CJson, from: &str, path: &str) -> CJsonResult<()> {
+        Self::add_op_to_array(array, &PatchOp::Copy { from: String::from(from), path: String::from(path) })
+    }
+
+    fn add_op_to_array(array: &mut CJson, op: &PatchOp) -> CJsonResult<()> {
+        if !array.is_array() {
+            return Err(CJsonError::TypeError);
+        }
+        array.add_item_to_array(op.to_cjson()?)
+    }
 }
 
 /// JSON Merge Patch utilities (RFC7386)
@@ -214,7 +484,9 @@ impl JsonMergePatch {
     /// * `patch` - The merge patch to apply
     /// 
     /// # Returns
-    /// A new CJson object with the merged result
+    /// A new CJson object with the merged result. `target` is consumed either
+    /// way: cJSON reuses or frees it, so it must not be dropped afterwards,
+    /// even when this fails.
     pub fn apply(target: &mut CJson, patch: &CJson) -> CJsonResult<CJson> {
         let ptr = unsafe {
             cJSONUtils_MergePatch(target.as_mut_ptr(), patch.as_ptr())
@@ -229,7 +501,9 @@ impl JsonMergePatch {
     /// * `patch` - The merge patch to apply
     /// 
     /// # Returns
-    /// A new CJson object with the merged result
+    /// A new CJson object with the merged result. `target` is consumed either
+    /// way: cJSON reuses or frees it, so it must not be dropped afterwards,
+    /// even when this fails.
     pub fn apply_case_sensitive(target: &mut CJson, patch: &CJson) -> CJsonResult<CJson> {
         let ptr = unsafe {
             cJSONUtils_MergePatchCaseSensitive(target.as_mut_ptr(), patch.as_ptr())
@@ -237,6 +511,63 @@ impl JsonMergePatch {
         unsafe { CJson::from_ptr(ptr) }
     }
 
+    /// Generate a JSON Merge Patch to transform 'from' into 'to' without modifying them.
+    /// 
+    /// Same as `generate`, but runs on copies so the caller's key order is preserved.
+    /// 
+    /// # Arguments
+    /// * `from` - The original JSON object
+    /// * `to` - The target JSON object
+    /// 
+    /// # Returns
+    /// A new CJson object containing the merge patch
+    pub fn diff(from: &CJson, to: &CJson) -> CJsonResult<CJson> {
+        generate_on_copies(from, to, Self::generate)
+    }
+
+    /// Generate the smallest JSON Merge Patch turning `current` into `desired`.
+    /// 
+    /// Unchanged members are left out and removed ones are written as `null`;
+    /// keys are compared case sensitively. Both inputs are left untouched, and
+    /// the patch is checked by applying it to a copy of `current`.
+    /// 
+    /// # Arguments
+    /// * `current` - The state the patch will be applied to
+    /// * `desired` - The state the patch should produce
+    /// 
+    /// # Returns
+    /// The merge patch, an empty object when nothing changed, or
+    /// InvalidOperation when no merge patch can produce `desired` (e.g. it
+    /// holds `null` members, which a merge patch can only express as removals)
+    pub fn minimal(current: &CJson, desired: &CJson) -> CJsonResult<CJson> {
+        let patch = match generate_on_copies(current, desired, Self::generate_case_sensitive) {
+            Ok(patch) => patch,
+            // cJSON gives no patch at all when there is nothing to change
+            Err(CJsonError::NullPointer) => return CJson::create_object(),
+            Err(e) => return Err(e),
+        };
+
+        let verified = current
+            .duplicate(true)
+            .and_then(|mut copy| Self::apply_case_sensitive(&mut copy, &patch))
+            .map(|result| {
+                let matches = result.structurally_eq(desired, true);
+                result.drop();
+                matches
+            });
+        match verified {
+            Ok(true) => Ok(patch),
+            Ok(false) => {
+                patch.drop();
+                Err(CJsonError::InvalidOperation)
+            }
+            Err(e) => {
+                patch.drop();
+                Err(e)
+            }
+        }
+    }
+
     /// Generate a JSON Merge Patch to transform 'from' into 'to'.
     /// 
     /// Note: This function modifies both 'from' and 'to' by sorting their keys.
@@ -272,6 +603,26 @@ impl JsonMergePatch {
     }
 }
 
+/// Run a generator that sorts its inputs on deep copies of them
+fn generate_on_copies<F>(from: &CJson, to: &CJson, generate: F) -> CJsonResult<CJson>
+where
+    F: FnOnce(&mut CJson, &mut CJson) -> CJsonResult<CJson>,
+{
+    let mut from_copy = from.duplicate(true)?;
+    let mut to_copy = match to.duplicate(true) {
+        Ok(to_copy) => to_copy,
+        Err(e) => {
+            from_copy.drop();
+            return Err(e);
+        }
+    };
+
+    let ret = generate(&mut from_copy, &mut to_copy);
+    from_copy.drop();
+    to_copy.drop();
+    ret
+}
+
 /// Utility functions for JSON object manipulation
 pub struct JsonUtils;
 
@@ -304,6 +655,66 @@ impl JsonUtils {
 /// Re-export CJsonRef for use with pointer operations
 pub use crate::cjson::CJsonRef;
 
+/// Parse `json`, read the number at the JSON Pointer `pointer` and free the tree.
+/// 
+/// # Returns
+/// The number, NotFound if the pointer does not resolve, or TypeError
+pub fn parse_get_f64(json: &str, pointer: &str) -> CJsonResult<f64> {
+    parse_get(json, pointer, |item| item.get_number_value())
+}
+
+/// Parse `json`, read the integer at the JSON Pointer `pointer` and free the tree.
+/// 
+/// # Returns
+/// The integer, NotFound if the pointer does not resolve, or TypeError
+pub fn parse_get_i64(json: &str, pointer: &str) -> CJsonResult<i64> {
+    parse_get(json, pointer, |item| item.get_i64())
+}
+
+/// Parse `json`, read the string at the JSON Pointer `pointer` and free the tree.
+/// 
+/// # Returns
+/// The string, NotFound if the pointer does not resolve, or TypeError
+pub fn parse_get_str(json: &str, pointer: &str) -> CJsonResult<String> {
+    parse_get(json, pointer, |item| item.get_string_value())
+}
+
+/// Parse `json`, read the boolean at the JSON Pointer `pointer` and free the tree.
+/// 
+/// # Returns
+/// The boolean, NotFound if the pointer does not resolve, or TypeError
+pub fn parse_get_bool(json: &str, pointer: &str) -> CJsonResult<bool> {
+    parse_get(json, pointer, |item| item.get_bool_value())
+}
+
+/// Check that every pointer in `rules` resolves to an item of the given type
+///
+/// Rules are checked in order and pointers are case sensitive.
+///
+/// # Returns
+/// * `Ok(())` - All rules hold
+/// * `Err(CJsonError::SchemaError)` - First rule that failed; `found` is `"missing"` when the pointer does not resolve
+pub fn validate_schema(doc: &CJson, rules: &[(&str, CJsonType)]) -> CJsonResult<()> {
+    for &(pointer, expected) in rules {
+        let found = match JsonPointer::get_case_sensitive(doc, pointer) {
+            Ok(item) => item.type_name(),
+            Err(CJsonError::NotFound) => "missing",
+            Err(e) => return Err(e),
+        };
+        if found != expected.name() {
+            return Err(CJsonError::SchemaError { pointer: String::from(pointer), expected: expected.name(), found });
+        }
+    }
+    Ok(())
+}
+
+fn parse_get<T>(json: &str, pointer: &str, read: impl FnOnce(&CJsonRef) -> CJsonResult<T>) -> CJsonResult<T> {
+    let root = CJson::parse(json)?;
+    let ret = JsonPointer::get_case_sensitive(&root, pointer).and_then(|item| read(&item));
+    root.drop();
+    ret
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -471,4 +882,263 @@ mod tests {
         assert!(result.has_object_item("name"));
         assert!(result.has_object_item("age"));
     }
+
+    #[test]
+    fn test_json_patch_apply_op() {
+        let mut obj = CJson::parse(r#"{"name":"John","age":30}"#).unwrap();
+
+        let ops = [
+            PatchOp::Replace { path: String::from("/age"), value: CJson::create_number(31.0).unwrap() },
+            PatchOp::Add { path: String::from("/city"), value: CJson::create_string("NYC").unwrap() },
+            PatchOp::Test { path: String::from("/name"), value: CJson::create_string("Jane").unwrap() },
+            PatchOp::Remove { path: String::from("/name") },
+        ];
+
+        let mut failed = None;
+        for (i, op) in ops.iter().enumerate() {
+            if let Err(e) = JsonPatch::apply_op(&mut obj, op) {
+                failed = Some((i, op.op_name(), e));
+                break;
+            }
+        }
+
+        assert_eq!(failed, Some((2, "test", CJsonError::InvalidOperation)));
+        assert_eq!(obj.get_object_item("age").unwrap().get_number_value().unwrap(), 31.0);
+        assert_eq!(obj.get_object_item("city").unwrap().get_string_value().unwrap(), "NYC");
+        assert!(obj.has_object_item("name"));
+    }
+
+    #[test]
+    fn test_json_patch_diff() {
+        let from = CJson::parse(r#"{"name":"John","age":30,"city":"NYC"}"#).unwrap();
+        let to = CJson::parse(r#"{"name":"John","age":31,"zip":"10001"}"#).unwrap();
+
+        let ops = JsonPatch::diff(&from, &to).unwrap();
+        assert_eq!(ops.len(), 3);
+
+        let mut seen = (false, false, false);
+        for op in ops.iter() {
+            match op {
+                PatchOp::Replace { path, value } if path == "/age" => {
+                    assert_eq!(value.get_number_value().unwrap(), 31.0);
+                    seen.0 = true;
+                }
+                PatchOp::Remove { path } if path == "/city" => seen.1 = true,
+                PatchOp::Add { path, value } if path == "/zip" => {
+                    assert_eq!(value.get_string_value().unwrap(), "10001");
+                    seen.2 = true;
+                }
+                other => panic!("unexpected op {:?}", other),
+            }
+        }
+        assert_eq!(seen, (true, true, true));
+
+        // Inputs are not sorted by diff
+        assert_eq!(from.print_unformatted().unwrap(), r#"{"name":"John","age":30,"city":"NYC"}"#);
+
+        // The operations own their values, dropping them frees everything
+        let (_, stats) = crate::alloc_counter::measure(|| drop(JsonPatch::diff(&from, &to).unwrap()));
+        assert!(stats.c_allocs > 0);
+        assert_eq!(stats.c_live, 0);
+        drop(ops);
+        from.drop();
+        to.drop();
+    }
+
+    #[test]
+    fn test_json_patch_add_move_and_copy() {
+        let mut obj = CJson::parse(r#"{"a":{"x":1},"b":{}}"#).unwrap();
+
+        let mut patches = CJson::create_array().unwrap();
+        JsonPatch::add_move(&mut patches, "/a/x", "/b/y").unwrap();
+        JsonPatch::add_copy(&mut patches, "/b/y", "/z").unwrap();
+        assert_eq!(patches.get_array_size().unwrap(), 2);
+
+        JsonPatch::apply(&mut obj, &patches).unwrap();
+
+        assert!(JsonPointer::get(&obj, "/a/x").is_err());
+        assert_eq!(JsonPointer::get(&obj, "/b/y").unwrap().get_number_value().unwrap(), 1.0);
+        assert_eq!(JsonPointer::get(&obj, "/z").unwrap().get_number_value().unwrap(), 1.0);
+
+        let mut not_array = CJson::create_object().unwrap();
+        assert_eq!(JsonPatch::add_move(&mut not_array, "/a", "/b"), Err(CJsonError::TypeError));
+    }
+
+    #[test]
+    fn test_json_patch_test() {
+        let obj = CJson::parse(r#"{"wifi":{"auth":3,"ssid":"home"}}"#).unwrap();
+
+        let three = CJson::create_number(3.0).unwrap();
+        let four = CJson::create_number(4.0).unwrap();
+
+        assert!(JsonPatch::test(&obj, "/wifi/auth", &three));
+        assert!(!JsonPatch::test(&obj, "/wifi/auth", &four));
+        assert!(!JsonPatch::test(&obj, "/wifi/missing", &three));
+    }
+
+    #[test]
+    fn test_diff_leaves_inputs_untouched() {
+        let from_json = r#"{"z":1,"m":{"y":true,"b":false},"a":"x"}"#;
+        let to_json = r#"{"z":2,"m":{"y":true},"a":"x","c":null}"#;
+        let from = CJson::parse(from_json).unwrap();
+        let to = CJson::parse(to_json).unwrap();
+
+        let merge_patch = JsonMergePatch::diff(&from, &to).unwrap();
+        assert_eq!(merge_patch.get_object_item("z").unwrap().get_number_value().unwrap(), 2.0);
+        assert!(merge_patch.get_object_item("m").unwrap().get_object_item("b").unwrap().is_null());
+
+        let patch = JsonPatch::diff_json(&from, &to).unwrap();
+        assert!(patch.is_array());
+
+        assert_eq!(from.print_unformatted().unwrap(), from_json);
+        assert_eq!(to.print_unformatted().unwrap(), to_json);
+
+        merge_patch.drop();
+        patch.drop();
+        from.drop();
+        to.drop();
+    }
+
+    #[test]
+    fn test_parse_get() {
+        let json = r#"{"ntp":{"port":123,"server":"pool.ntp.org","enabled":true}}"#;
+        assert_eq!(parse_get_f64(json, "/ntp/port"), Ok(123.0));
+        assert_eq!(parse_get_i64(json, "/ntp/port"), Ok(123));
+        assert_eq!(parse_get_str(json, "/ntp/server").unwrap(), "pool.ntp.org");
+        assert_eq!(parse_get_bool(json, "/ntp/enabled"), Ok(true));
+
+        assert_eq!(parse_get_f64(json, "/ntp/timeout"), Err(CJsonError::NotFound));
+        assert_eq!(parse_get_f64(json, "/ntp/server"), Err(CJsonError::TypeError));
+    }
+
+    #[test]
+    fn test_validate_schema() {
+        let doc = CJson::parse(r#"{"wifi":{"ssid":"garden","channel":6},"ntp":["pool.ntp.org"],"debug":false}"#).unwrap();
+        let rules = [
+            ("/wifi", CJsonType::Object),
+            ("/wifi/ssid", CJsonType::String),
+            ("/wifi/channel", CJsonType::Number),
+            ("/ntp/0", CJsonType::String),
+            ("/debug", CJsonType::Bool),
+        ];
+        assert_eq!(validate_schema(&doc, &rules), Ok(()));
+        assert_eq!(validate_schema(&doc, &[]), Ok(()));
+
+        let missing = validate_schema(&doc, &[("/wifi", CJsonType::Object), ("/wifi/password", CJsonType::String)]);
+        assert_eq!(missing, Err(CJsonError::SchemaError {
+            pointer: String::from("/wifi/password"),
+            expected: "string",
+            found: "missing",
+        }));
+
+        let wrong_type = validate_schema(&doc, &[("/ntp", CJsonType::Object), ("/debug", CJsonType::Number)]);
+        assert_eq!(wrong_type, Err(CJsonError::SchemaError {
+            pointer: String::from("/ntp"),
+            expected: "object",
+            found: "array",
+        }));
+        doc.drop();
+    }
+
+    #[test]
+    fn test_json_pointer_find_all() {
+        let config = CJson::parse(
+            r#"{"debug":false,"wifi":{"enabled":true,"dhcp":false},"zones":[{"on":false},{"on":true}],"name":"false"}"#,
+        )
+        .unwrap();
+        let target = CJson::create_false().unwrap();
+        let found = JsonPointer::find_all(&config, &target, true).unwrap();
+        assert_eq!(found, ["/debug", "/wifi/dhcp", "/zones/0/on"]);
+        target.drop();
+
+        let target = CJson::parse(r#"{"on":true}"#).unwrap();
+        assert_eq!(JsonPointer::find_all(&config, &target, true).unwrap(), ["/zones/1"]);
+        target.drop();
+
+        let target = CJson::parse(r#"{"ON":true}"#).unwrap();
+        assert!(JsonPointer::find_all(&config, &target, true).unwrap().is_empty());
+        assert_eq!(JsonPointer::find_all(&config, &target, false).unwrap(), ["/zones/1"]);
+        target.drop();
+
+        assert_eq!(JsonPointer::find_all(&config, &config, true).unwrap(), [""]);
+        config.drop();
+
+        // A key that is not UTF-8 cannot be written as a pointer, so the search fails
+        let mut doc = CJson::create_object().unwrap();
+        doc.add_item_to_object("a", CJson::create_false().unwrap()).unwrap();
+        doc.add_item_to_object("b", CJson::create_false().unwrap()).unwrap();
+        let member = JsonPointer::get(&doc, "/b").unwrap().as_ptr() as *mut cJSON;
+        unsafe { *(*member).string = 0xffu8 as c_char };
+        let target = CJson::create_false().unwrap();
+        assert_eq!(JsonPointer::find_all(&doc, &target, true), Err(CJsonError::InvalidUtf8));
+        target.drop();
+        doc.drop();
+    }
+
+    #[test]
+    fn test_json_pointer_replace() {
+        let mut config = CJson::parse(r#"{"wifi":{"ssid":"garden","channel":6},"zones":[1,2,3]}"#).unwrap();
+        JsonPointer::replace(&mut config, "/wifi/channel", CJson::create_number(11.0).unwrap()).unwrap();
+        JsonPointer::replace(&mut config, "/zones/1", CJson::create_string("two").unwrap()).unwrap();
+        assert_eq!(
+            config.print_unformatted().unwrap(),
+            r#"{"wifi":{"ssid":"garden","channel":11},"zones":[1,"two",3]}"#
+        );
+
+        let result = JsonPointer::replace(&mut config, "/wifi/password", CJson::create_null().unwrap());
+        assert_eq!(result, Err(CJsonError::NotFound));
+        let result = JsonPointer::replace(&mut config, "/zones/3", CJson::create_null().unwrap());
+        assert_eq!(result, Err(CJsonError::NotFound));
+        // Without the leading '/' cJSONUtils would resolve to the root itself
+        let result = JsonPointer::replace(&mut config, "wifi", CJson::create_null().unwrap());
+        assert_eq!(result, Err(CJsonError::NotFound));
+        assert_eq!(
+            config.print_unformatted().unwrap(),
+            r#"{"wifi":{"ssid":"garden","channel":11},"zones":[1,"two",3]}"#
+        );
+        config.drop();
+    }
+
+    #[test]
+    fn test_json_merge_patch_minimal() {
+        let current = CJson::parse(
+            r#"{"name":"garden","wifi":{"ssid":"home","channel":6,"dhcp":true},"zones":[1,2],"debug":false}"#,
+        )
+        .unwrap();
+        let desired = CJson::parse(
+            r#"{"name":"garden","wifi":{"ssid":"home","channel":11,"dhcp":true},"zones":[1,2]}"#,
+        )
+        .unwrap();
+
+        let patch = JsonMergePatch::minimal(&current, &desired).unwrap();
+        assert_eq!(patch.print_unformatted().unwrap(), r#"{"debug":null,"wifi":{"channel":11}}"#);
+        patch.drop();
+
+        // Inputs keep their key order
+        assert!(current.print_unformatted().unwrap().starts_with(r#"{"name":"garden","wifi""#));
+
+        let patch = JsonMergePatch::minimal(&current, &current).unwrap();
+        assert_eq!(patch.print_unformatted().unwrap(), "{}");
+        patch.drop();
+
+        // A null member cannot be written by a merge patch
+        let with_null = CJson::parse(r#"{"name":null}"#).unwrap();
+        let empty = CJson::create_object().unwrap();
+        assert_eq!(JsonMergePatch::minimal(&empty, &with_null).err(), Some(CJsonError::InvalidOperation));
+
+        with_null.drop();
+        empty.drop();
+        current.drop();
+        desired.drop();
+    }
+
+    #[test]
+    fn test_patch_op_frees_value() {
+        let (_, stats) = crate::alloc_counter::measure(|| {
+            let op = PatchOp::Add { path: String::from("/a"), value: CJson::parse(r#"{"x":[1,2]}"#).unwrap() };
+            let item = op.to_cjson().unwrap();
+            item.drop();
+        });
+        assert_eq!(stats.c_live, 0);
+    }
 }
@@ -28,6 +28,7 @@ use crate::cjson::CJsonError;
 use crate::cjson::CJson;
 use crate::cjson::CJsonRef;
 use crate::cjson_ffi::cJSON_Duplicate;
+use crate::ser::{AsStr, MapKey};
 use core::fmt::Write;
 
 use alloc::vec;
@@ -36,62 +37,115 @@ use alloc::string::String;
 use alloc::collections::BTreeMap;
 
 
+impl<T: From<String>> Deserialize for AsStr<T> {
+    fn deserialize<D: Deserializer>(deserializer: &mut D, name: &str) -> core::result::Result<Self, D::Error> {
+        deserializer.deserialize_string(name).map(|s| AsStr(T::from(s)))
+    }
+}
+
+/// What integer fields do with a value outside the target type's range
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumberRange {
+    /// Fail with `NumberOutOfRange`
+    #[default]
+    Error,
+    /// Clamp to the nearest bound, e.g. `300` into `u8` is `255`
+    Saturate,
+    /// Wrap around like an `as` cast between integers, e.g. `300` into `u8` is `44`
+    Wrap,
+}
+
 pub struct JsonDeserializer {
     stack: BTreeMap<String, CJson>,
     stack_name: Vec<String>,
     struct_depth: usize,  // Tracks how many struct_start pushes we've done
+    coerce: bool,         // Accept loosely-typed scalars (e.g. 1/"true" for bool)
+    bytes_as_text: bool,  // Read byte buffers as plain text, skipping hex detection
+    elements: Vec<(usize, usize)>,  // Stack length and struct depth before each push_element
+    number_range: NumberRange,  // Out-of-range integer policy
 }
 
 impl Deserializer for JsonDeserializer {
     type Error = CJsonError;
 
     fn deserialize_bool(&mut self, name: &str) -> core::result::Result<bool, Self::Error> {
-        let item = self.get_item(name)?;
-        item.get_bool_value()
+        let coerce = self.coerce;
+        self.with_item(name, |item| {
+            if item.is_bool() {
+                return item.get_bool_value();
+            } else if !coerce {
+                return Err(unexpected("bool", item));
+            }
+
+            // Lenient forms: 0/1 numbers and "true"/"false"/"0"/"1" strings
+            if item.is_number() {
+                let n = item.get_number_value()?;
+                if n == 0.0 {
+                    return Ok(false);
+                } else if n == 1.0 {
+                    return Ok(true);
+                }
+            } else if item.is_string() {
+                let s = item.get_string_value()?;
+                if s.eq_ignore_ascii_case("true") || s == "1" {
+                    return Ok(true);
+                } else if s.eq_ignore_ascii_case("false") || s == "0" {
+                    return Ok(false);
+                }
+            }
+
+            Err(unexpected("bool", item))
+        })
     }
 
     fn deserialize_u8(&mut self, name: &str) -> core::result::Result<u8, Self::Error> {
-        let v = self.deserialize_u64(name)?;
-        if v <= u8::MAX as u64 { Ok(v as u8) } else { Err(CJsonError::TypeError) }
+        let v = self.get_number_in_range(name, u8::MIN as f64, u8::MAX as f64)?;
+        Ok(v as u8)
     }
 
     fn deserialize_i8(&mut self, name: &str) -> core::result::Result<i8, Self::Error> {
-        let v = self.deserialize_i64(name)?;
-        if v >= i8::MIN as i64 && v <= i8::MAX as i64 { Ok(v as i8) } else { Err(CJsonError::TypeError) }
+        let v = self.get_number_in_range(name, i8::MIN as f64, i8::MAX as f64)?;
+        Ok(v as i8)
     }
 
 
     fn deserialize_u16(&mut self, name: &str) -> core::result::Result<u16, Self::Error> {
-        let v = self.deserialize_u64(name)?;
-        if v <= u16::MAX as u64 { Ok(v as u16) } else { Err(CJsonError::TypeError) }
+        let v = self.get_number_in_range(name, u16::MIN as f64, u16::MAX as f64)?;
+        Ok(v as u16)
     }
 
     fn deserialize_i16(&mut self, name: &str) -> core::result::Result<i16, Self::Error> {
-        let v = self.deserialize_i64(name)?;
-        if v >= i16::MIN as i64 && v <= i16::MAX as i64 { Ok(v as i16) } else { Err(CJsonError::TypeError) }
+        let v = self.get_number_in_range(name, i16::MIN as f64, i16::MAX as f64)?;
+        Ok(v as i16)
     }
 
     fn deserialize_u32(&mut self, name: &str) -> core::result::Result<u32, Self::Error> {
-        let v = self.deserialize_u64(name)?;
-        if v <= u32::MAX as u64 { Ok(v as u32) } else { Err(CJsonError::TypeError) }
+        let v = self.get_number_in_range(name, u32::MIN as f64, u32::MAX as f64)?;
+        Ok(v as u32)
     }
 
     fn deserialize_i32(&mut self, name: &str) -> core::result::Result<i32, Self::Error> {
-        let v = self.deserialize_i64(name)?;
-        if v >= i32::MIN as i64 && v <= i32::MAX as i64 { Ok(v as i32) } else { Err(CJsonError::TypeError) }
+        let v = self.get_number_in_range(name, i32::MIN as f64, i32::MAX as f64)?;
+        Ok(v as i32)
     }
 
     fn deserialize_u64(&mut self, name: &str) -> core::result::Result<u64, Self::Error> {
+        // Raw digits beyond 2^53 would be rounded by f64
         let item = self.get_item(name)?;
-        let n = item.get_number_value()?;
-        if n < 0.0 { return Err(CJsonError::TypeError); }
-        Ok(n as u64)
+        if item.is_raw() && let Ok(v) = item.get_u64() {
+            return Ok(v);
+        }
+        let v = self.get_number_in_range(name, u64::MIN as f64, u64::MAX as f64)?;
+        Ok(v as u64)
     }
 
     fn deserialize_i64(&mut self, name: &str) -> core::result::Result<i64, Self::Error> {
         let item = self.get_item(name)?;
-        let n = item.get_number_value()?;
-        Ok(n as i64)
+        if item.is_raw() && let Ok(v) = item.get_i64() {
+            return Ok(v);
+        }
+        let v = self.get_number_in_range(name, i64::MIN as f64, i64::MAX as f64)?;
+        Ok(v as i64)
     }
 
     fn deserialize_u128(&mut self, name: &str) -> core::result::Result<u128, Self::Error> {
@@ -105,14 +159,16 @@ impl Deserializer for JsonDeserializer {
     }
 
     fn deserialize_f32(&mut self, name: &str) -> core::result::Result<f32, Self::Error> {
-        let item = self.get_item(name)?;
-        let n = item.get_number_value()?;
+        let n = self.get_number(name)?;
+        // A finite f64 beyond f32 range would silently become inf
+        if n.is_finite() && n.abs() > f32::MAX as f64 {
+            return Err(CJsonError::NumberOutOfRange { value: n, min: f32::MIN as f64, max: f32::MAX as f64 });
+        }
         Ok(n as f32)
     }
 
     fn deserialize_f64(&mut self, name: &str) -> core::result::Result<f64, Self::Error> {
-        let item = self.get_item(name)?;
-        item.get_number_value()
+        self.get_number(name)
     }
 
     fn deserialize_bytes(&mut self, name: &str, buffer: &mut [u8]) -> core::result::Result<usize, Self::Error> {
@@ -123,7 +179,8 @@ impl Deserializer for JsonDeserializer {
             
             // Check if the string is a hex-encoded string
             // (even length and all chars are 0-9, a-f, A-F)
-            let is_hex = s.len() % 2 == 0 && 
+            let is_hex = !self.bytes_as_text &&
+                         s.len() % 2 == 0 && 
                          s.len() > 0 &&
                          s.chars().all(|c| c.is_ascii_hexdigit());
             
@@ -156,21 +213,22 @@ impl Deserializer for JsonDeserializer {
             return Ok(copy_len);
         }
 
-        Err(CJsonError::TypeError)
+        Err(unexpected("string or array", &item))
     }
 
     fn deserialize_string(&mut self, name: &str) -> core::result::Result<String, Self::Error> {
-        let item = self.get_item(name)?;
-        if item.is_string() {
-            item.get_string_value()
-        } else if item.is_number() {
-            let n = item.get_number_value()?;
-            let mut s = String::new();
-            let _ = write!(&mut s, "{}", n);
-            Ok(s)
-        } else {
-            Err(CJsonError::TypeError)
-        }
+        self.with_item(name, |item| {
+            if item.is_string() {
+                item.get_string_value()
+            } else if item.is_number() {
+                let n = item.get_number_value()?;
+                let mut s = String::new();
+                let _ = write!(&mut s, "{}", n);
+                Ok(s)
+            } else {
+                Err(unexpected("string", item))
+            }
+        })
     }
 
     fn deserialize_vec<T>(&mut self, name: &str) -> core::result::Result<Vec<T>, Self::Error>
@@ -178,7 +236,7 @@ impl Deserializer for JsonDeserializer {
         T: Deserialize {
         let item = self.get_item(name)?;
         if !item.is_array() {
-            return Err(CJsonError::TypeError);
+            return Err(unexpected("array", &item));
         }
 
         let size = item.get_array_size()?;
@@ -187,21 +245,22 @@ impl Deserializer for JsonDeserializer {
         for i in 0..size {
             let elem_ref = item.get_array_item(i)?;
             // duplicate element and push as current context
-            let dup_ptr = unsafe { cJSON_Duplicate(elem_ref.as_ptr(), 1) };
-            let obj = unsafe { CJson::from_ptr(dup_ptr) }?;
             let mut idx_s = String::new();
             let _ = write!(&mut idx_s, "{}", i);
             let key = [name, "[", idx_s.as_str(), "]"].concat();
-            self.stack_name.push(key.clone());
-            self.stack.insert(key.clone(), obj);
+            let saved_len = self.stack_name.len();
+            let saved_depth = self.struct_depth;
+            self.push_copy(key, &elem_ref)?;
 
             // let the element's Deserialize implementation operate on current top (use empty name)
-            let v = T::deserialize(self, "")?;
-            out.push(v);
+            let ret = T::deserialize(self, "");
 
-            // pop element context
-            let last = self.stack_name.pop().unwrap();
-            let _ = self.stack.remove(&last);
+            // pop element context, including anything a failed element left behind;
+            // on error `out` is dropped with the elements already deserialized
+            self.unwind_to(saved_len);
+            self.struct_depth = saved_depth;
+
+            out.push(ret?);
         }
 
         Ok(out)
@@ -210,18 +269,18 @@ impl Deserializer for JsonDeserializer {
     fn deserialize_array<T, const N: usize>(&mut self, name: &str) -> core::result::Result<[T; N], Self::Error>
     where
         T: Deserialize {
-        let vec: Vec<T> = self.deserialize_vec(name)?;
-        if vec.len() != N {
-            return Err(CJsonError::InvalidOperation);
-        }
+        // A string holds a byte buffer such as `[u8; N]`; other element types
+        // still get the "expected array" error from deserialize_vec
+        let vec: Vec<T> = if self.get_item(name)?.is_string() && self.reads_bytes::<T>() {
+            let bytes: [u8; N] = self.deserialize_byte_array(name)?;
+            self.deserialize_byte_elements(&bytes)?
+        } else {
+            self.deserialize_vec(name)?
+        };
+        let actual = vec.len();
 
-        // convert Vec<T> into [T; N]
-        let mut arr: core::mem::MaybeUninit<[T; N]> = core::mem::MaybeUninit::uninit();
-        let ptr = arr.as_mut_ptr() as *mut T;
-        for (i, v) in vec.into_iter().enumerate() {
-            unsafe { ptr.add(i).write(v); }
-        }
-        Ok(unsafe { arr.assume_init() })
+        // convert Vec<T> into [T; N]; on mismatch the Vec (and its elements) is dropped
+        vec.try_into().map_err(|_| CJsonError::ArrayLengthMismatch { expected: N, actual })
     }
 
     /// Begin deserializing a struct with the given name.
@@ -271,7 +330,9 @@ impl Deserializer for JsonDeserializer {
         if self.struct_depth > 0 {
             self.struct_depth -= 1;
             if let Some(name) = self.stack_name.pop() {
-                let _ = self.stack.remove(&name);
+                if let Some(obj) = self.stack.remove(&name) {
+                    obj.drop();
+                }
             }
         }
 
@@ -282,6 +343,122 @@ impl Deserializer for JsonDeserializer {
 }
 
 impl JsonDeserializer {
+    /// Read a number and bring its integral part into `[min, max]` according
+    /// to the `NumberRange` policy.
+    ///
+    /// Returns the truncated value; the error carries the original value.
+    fn get_number_in_range(&mut self, name: &str, min: f64, max: f64) -> core::result::Result<f64, CJsonError> {
+        let n = self.get_number(name)?;
+        let t = trunc(n);
+        // `t` is integral, so `t < max + 1.0` is `t <= max`. For 64-bit types
+        // `max as f64` rounds up to 2^64 or 2^63, and adding one leaves that
+        // bound exclusive, so values `as` would saturate are still reported.
+        if t >= min && t < max + 1.0 {
+            return Ok(t);
+        }
+        match self.number_range {
+            NumberRange::Error => Err(CJsonError::NumberOutOfRange { value: n, min, max }),
+            NumberRange::Saturate => Ok(t.clamp(min, max)),
+            NumberRange::Wrap => {
+                let span = max - min + 1.0;
+                let offset = (t - min) % span;
+                Ok(if offset < 0.0 { offset + span } else { offset } + min)
+            }
+        }
+    }
+
+    /// Read a `[u8; N]` written through the byte encodings of `JsonSerializer`.
+    ///
+    /// Text may be shorter than `N` (the rest stays zero), hex and number arrays must match exactly.
+    fn deserialize_byte_array<const N: usize>(&mut self, name: &str) -> core::result::Result<[u8; N], CJsonError> {
+        let item = self.get_item(name)?;
+        let padded = self.bytes_as_text && item.is_string();
+        let actual = if item.is_array() {
+            item.get_array_size()?
+        } else if padded {
+            item.get_string_value()?.len()
+        } else if item.is_string() {
+            item.get_string_value()?.len() / 2
+        } else {
+            return Err(unexpected("string or array", &item));
+        };
+        if actual > N || (!padded && actual != N) {
+            return Err(CJsonError::ArrayLengthMismatch { expected: N, actual });
+        }
+
+        let mut buffer = [0u8; N];
+        self.deserialize_bytes(name, &mut buffer)?;
+        Ok(buffer)
+    }
+
+    /// Whether `T` reads exactly the values of a byte: 0 and 255 but not 256.
+    fn reads_bytes<T: Deserialize>(&mut self) -> bool {
+        self.deserialize_int_elements::<T>(&[0, 255]).is_ok()
+            && self.deserialize_int_elements::<T>(&[256]).is_err()
+    }
+
+    /// Let each `T` read one of `bytes`, as the numbers they are.
+    fn deserialize_byte_elements<T: Deserialize>(&mut self, bytes: &[u8]) -> core::result::Result<Vec<T>, CJsonError> {
+        let values: Vec<i32> = bytes.iter().map(|&b| i32::from(b)).collect();
+        self.deserialize_int_elements(&values)
+    }
+
+    /// Let each `T` read one of `values` from a temporary int array.
+    fn deserialize_int_elements<T: Deserialize>(&mut self, values: &[i32]) -> core::result::Result<Vec<T>, CJsonError> {
+        let len = self.stack_name.len();
+        let mut key = String::new();
+        let _ = write!(&mut key, "#bytes{}", len);
+        self.stack_name.push(key.clone());
+        self.stack.insert(key, CJson::create_int_array(values)?);
+
+        let ret = self.deserialize_vec("");
+        self.unwind_to(len);
+        ret
+    }
+
+    /// Read a number (or raw numeric text).
+    fn get_number(&mut self, name: &str) -> core::result::Result<f64, CJsonError> {
+        let item = self.get_item(name)?;
+        item.get_number_value().map_err(|_| unexpected("number", &item))
+    }
+
+    /// Run `read` on the named item. A Raw node is parsed first, so `read` sees
+    /// the value its text holds (e.g. `true` or `"text"`) instead of a Raw node.
+    fn with_item<T>(
+        &mut self,
+        name: &str,
+        read: impl FnOnce(&CJsonRef) -> core::result::Result<T, CJsonError>,
+    ) -> core::result::Result<T, CJsonError> {
+        let item = self.get_item(name)?;
+        if !item.is_raw() {
+            return read(&item);
+        }
+        // Printing a Raw node gives back its text verbatim
+        let parsed = CJson::parse(&item.print_unformatted()?).map_err(|_| CJsonError::ParseError)?;
+        let ret = unsafe { CJsonRef::from_ptr(parsed.as_ptr() as *mut _) }.and_then(|value| read(&value));
+        parsed.drop();
+        ret
+    }
+
+    /// Push an owned copy of `elem` under `key`, making it the current context.
+    fn push_copy(&mut self, key: String, elem: &CJsonRef) -> core::result::Result<(), CJsonError> {
+        let obj = elem.to_owned()?;
+        self.stack_name.push(key.clone());
+        self.stack.insert(key, obj);
+        Ok(())
+    }
+
+    /// Pop stack entries until only `len` remain, freeing the owned copies.
+    fn unwind_to(&mut self, len: usize) {
+        while self.stack_name.len() > len {
+            if let Some(name) = self.stack_name.pop() {
+                if let Some(obj) = self.stack.remove(&name) {
+                    obj.drop();
+                }
+            }
+        }
+    }
+
     fn get_item(&mut self, name: &str) -> core::result::Result<CJsonRef, CJsonError> {
         // current top key
         let cur_key = match self.stack_name.last() {
@@ -306,25 +483,140 @@ impl JsonDeserializer {
 
 impl JsonDeserializer {
     
+    /// Parse `json` and deserialize from the resulting tree.
+    ///
+    /// cJSON reads numbers as `f64`, so integers beyond 2^53 come back rounded;
+    /// `from_cjson(CJson::parse_preserve_numbers(json)?)` reads 64-bit fields exactly.
     pub fn parse(json: &str) -> CJsonResult<Self>  {
+        Ok(Self::from_cjson(CJson::parse(json)?))
+    }
 
-
+    /// Deserialize from an already built tree, taking ownership of it.
+    ///
+    /// The tree is freed by `drop`.
+    pub fn from_cjson(root: CJson) -> Self {
         let mut stack = BTreeMap::<String, CJson>::new();
-        stack.insert(String::from(""), CJson::parse(json)?);
+        stack.insert(String::from(""), root);
 
-        Ok(Self {
+        Self {
             stack,
             stack_name: vec![String::from("")],
             struct_depth: 0,
-        })
+            coerce: false,
+            bytes_as_text: false,
+            elements: Vec::new(),
+            number_range: NumberRange::Error,
+        }
+    }
+
+    /// Read byte buffers (e.g. `Bytes<N>`) as plain text.
+    ///
+    /// Strings are copied verbatim without hex auto-detection; arrays of
+    /// numbers are still accepted. Matches `JsonSerializer::set_bytes_as_text`.
+    pub fn set_bytes_as_text(&mut self, enable: bool) {
+        self.bytes_as_text = enable;
+    }
+
+    /// Enable or disable lenient type coercion.
+    ///
+    /// When enabled, bool fields also accept the numbers `0`/`1` and the
+    /// strings `"true"`/`"false"`/`"0"`/`"1"` (case-insensitive).
+    pub fn set_coercion(&mut self, coerce: bool) {
+        self.coerce = coerce;
     }
 
+    /// Choose what integer fields do with out-of-range values (default `NumberRange::Error`).
+    pub fn set_number_range(&mut self, policy: NumberRange) {
+        self.number_range = policy;
+    }
+
+    /// Capture the named field (or the current node when `name` is empty)
+    /// as an independent deep copy, e.g. for catch-all fields.
+    ///
+    /// The caller owns the returned tree and must `drop` it.
+    pub fn deserialize_raw(&mut self, name: &str) -> CJsonResult<CJson> {
+        self.get_item(name)?.to_owned()
+    }
+
+    /// Read the object `name` (or the current node when `name` is empty) as a map,
+    /// parsing each key with `MapKey`. Counterpart of `JsonSerializer::serialize_map`.
+    ///
+    /// A key that does not parse fails with `UnexpectedType`; with repeated keys the last one wins.
+    pub fn deserialize_map<K, V>(&mut self, name: &str) -> CJsonResult<BTreeMap<K, V>>
+    where
+        K: MapKey,
+        V: Deserialize {
+        let object = self.get_item(name)?;
+        if !object.is_object() {
+            return Err(unexpected("object", &object));
+        }
+
+        let mut map = BTreeMap::new();
+        for (key, value) in object.object_entries()? {
+            let key = K::parse_key(&key)?;
+            self.push_element(&value)?;
+            let value = V::deserialize(self, "");
+            self.pop_element()?;
+            map.insert(key, value?);
+        }
+        Ok(map)
+    }
+
+    /// Make a copy of `elem` the current context, so `T::deserialize(self, "")`
+    /// reads it. Lets code for custom containers reuse the engine per element.
+    ///
+    /// Every push must be matched by a `pop_element`.
+    pub fn push_element(&mut self, elem: &CJsonRef) -> CJsonResult<()> {
+        let len = self.stack_name.len();
+        // Not "[i]": deserialize_vec names its elements that way
+        let mut key = String::new();
+        let _ = write!(&mut key, "#elem{}", len);
+        self.push_copy(key, elem)?;
+        self.elements.push((len, self.struct_depth));
+        Ok(())
+    }
+
+    /// Free the element pushed by `push_element` and restore the previous context,
+    /// including anything a failed element deserialization left behind.
+    pub fn pop_element(&mut self) -> CJsonResult<()> {
+        let (len, depth) = self.elements.pop().ok_or(CJsonError::InvalidOperation)?;
+        self.unwind_to(len);
+        self.struct_depth = depth;
+        Ok(())
+    }
+
+    /// Free every tree the deserializer owns and clear the state.
+    ///
+    /// Also runs when the deserializer goes out of scope; calling it more than once is harmless.
     pub fn drop(&mut self) {
-        if let Some(obj) = self.stack.first_entry() {
-            obj.get().drop();
+        // Each entry is an independent copy, the root included
+        for (_, obj) in core::mem::take(&mut self.stack) {
+            obj.drop();
         }
-        self.stack.clear();
         self.stack_name.clear();
+        self.elements.clear();
+        self.struct_depth = 0;
+    }
+
+}
+
+impl Drop for JsonDeserializer {
+    fn drop(&mut self) {
+        JsonDeserializer::drop(self);
+    }
+}
+
+/// `f64::trunc`, which `core` does not have
+fn trunc(n: f64) -> f64 {
+    // From 2^52 on every finite `f64` is integral; NaN and infinities pass through
+    if n.abs() < 4_503_599_627_370_496.0 {
+        n as i64 as f64
+    } else {
+        n
     }
+}
 
-}
\ No newline at end of file
+/// Type mismatch naming what was found in place of `expected`.
+fn unexpected(expected: &'static str, found: &CJsonRef) -> CJsonError {
+    CJsonError::UnexpectedType { expected, found: found.type_name() }
+}
@@ -34,15 +34,25 @@ mod cjson;
 pub(crate) mod cjson_utils_ffi;
 mod cjson_utils;
 
+#[cfg(test)]
+mod alloc_counter;
+
 #[cfg(feature = "osal_rs")]
 pub mod ser;
 
 #[cfg(feature = "osal_rs")]
 pub mod de;
 
+#[cfg(feature = "osal_rs")]
+pub mod stream;
+
+#[cfg(feature = "osal_rs")]
+pub mod slice;
+
 // Re-export main types for convenience
-pub use cjson::{CJson, CJsonRef, CJsonResult, CJsonError};
-pub use cjson_utils::{JsonPointer, JsonPatch, JsonMergePatch, JsonUtils};
+pub use cjson::{ArrayIter, ArrayIterMut, CJson, CJsonMut, CJsonRef, CJsonResult, CJsonError, CJsonType, ObjectIndex, ParseLimits, offset_to_line_col};
+pub use cjson_utils::{JsonPointer, JsonPatch, JsonMergePatch, JsonUtils, PatchOp};
+pub use cjson_utils::{parse_get_bool, parse_get_f64, parse_get_i64, parse_get_str, validate_schema};
 #[cfg(feature = "osal_rs")]
 use osal_rs_serde::{Deserialize, Result, Serialize};
 
@@ -101,17 +111,176 @@ where
 
     let mut serializer = JsonSerializer::new();
 
-    value.serialize("", &mut serializer).map_err(|e| {
+    let ret = value.serialize("", &mut serializer).map_err(|e| {
         log_error!(APP_TAG, "Serialization error: {}", e);
         osal_rs_serde::Error::InvalidData
-    })?;
-    
-    let json = serializer.print_unformatted().map_err(|e| {
+    }).and_then(|_| serializer.print_unformatted().map_err(|e| {
         log_error!(APP_TAG, "Failed to print JSON: {}", e);
         osal_rs_serde::Error::InvalidData
-    })?;
-    
-    Ok(json)
+    }));
+
+    serializer.reset();
+
+    ret
+}
+
+/// Serialize a value into a `CJson` tree (the caller must `drop` it)
+#[cfg(feature = "osal_rs")]
+pub fn to_cjson<T>(value: &T) -> CJsonResult<CJson>
+where
+    T: Serialize
+{
+    use crate::ser::JsonSerializer;
+
+    let mut serializer = JsonSerializer::new();
+    let ret = value.serialize("", &mut serializer).and_then(|_| serializer.take());
+    serializer.reset();
+    ret
+}
+
+/// Serialize a value as unformatted JSON into `buf` without heap allocations
+///
+/// Returns the number of bytes written, or `CapacityExceeded` if `buf` is too small.
+/// As with `to_json`, the value must be a struct: a top-level array or scalar
+/// fails with `InvalidOperation`.
+#[cfg(feature = "osal_rs")]
+pub fn to_slice<T>(value: &T, buf: &mut [u8]) -> CJsonResult<usize>
+where
+    T: Serialize
+{
+    use crate::slice::SliceSerializer;
+
+    let mut serializer: SliceSerializer = SliceSerializer::new(buf);
+    value.serialize("", &mut serializer)?;
+    serializer.finish()
+}
+
+/// Check that `doc` is structurally equal (case-sensitive keys) to the serialized `value`
+#[cfg(feature = "osal_rs")]
+pub fn cjson_eq_value<T>(doc: &CJson, value: &T) -> CJsonResult<bool>
+where
+    T: Serialize
+{
+    let expected = to_cjson(value)?;
+    let ret = doc.compare(&expected, true);
+    expected.drop();
+    Ok(ret)
+}
+
+/// Deserialize a value straight from a `CJson` tree, without printing and reparsing
+///
+/// Works on a duplicate, `value` is left untouched.
+#[cfg(feature = "osal_rs")]
+pub fn from_cjson<T>(value: &CJson) -> CJsonResult<T>
+where
+    T: Deserialize
+{
+    use crate::de::JsonDeserializer;
+
+    let mut deserializer = JsonDeserializer::from_cjson(value.duplicate(true)?);
+    let ret = T::deserialize(&mut deserializer, "");
+    deserializer.drop();
+    ret
+}
+
+/// Build the RFC6902 patch turning the serialized `from` into the serialized `to`
+#[cfg(feature = "osal_rs")]
+pub fn diff_values<T>(from: &T, to: &T) -> CJsonResult<CJson>
+where
+    T: Serialize
+{
+    let mut from = to_cjson(from)?;
+    let mut to = match to_cjson(to) {
+        Ok(to) => to,
+        Err(e) => {
+            from.drop();
+            return Err(e);
+        }
+    };
+
+    // Both trees are scratch copies, so the generator may sort them
+    let ret = JsonPatch::generate_case_sensitive(&mut from, &mut to);
+    from.drop();
+    to.drop();
+    ret
+}
+
+/// Apply an RFC6902 `patch` to the serialized `value` and deserialize the result
+///
+/// A patch that fails to apply gives `InvalidOperation`; a patched document
+/// that no longer fits `T` gives the deserializer's error (`NotFound` for a
+/// missing field, `UnexpectedType` for a changed type).
+#[cfg(feature = "osal_rs")]
+pub fn apply_patch_to_value<T>(value: &T, patch: &CJson) -> CJsonResult<T>
+where
+    T: Serialize + Deserialize
+{
+    use crate::de::JsonDeserializer;
+
+    let mut document = to_cjson(value)?;
+    if let Err(e) = JsonPatch::apply_case_sensitive(&mut document, patch) {
+        document.drop();
+        return Err(e);
+    }
+
+    let mut deserializer = JsonDeserializer::from_cjson(document);
+    let ret = T::deserialize(&mut deserializer, "");
+    deserializer.drop();
+    ret
+}
+
+/// Apply an RFC7386 merge `patch` to the serialized `value` and deserialize the result
+///
+/// A `null` in the patch removes the field, so the result only deserializes if
+/// `T` can do without it (otherwise `NotFound`).
+#[cfg(feature = "osal_rs")]
+pub fn merge_patch_value<T>(value: &T, patch: &CJson) -> CJsonResult<T>
+where
+    T: Serialize + Deserialize
+{
+    use crate::de::JsonDeserializer;
+
+    let mut document = to_cjson(value)?;
+    // cJSON hands back the merged tree, which replaces `document`. When it
+    // fails it has already freed `document`, so dropping it here would free
+    // it twice.
+    let merged = JsonMergePatch::apply_case_sensitive(&mut document, patch)?;
+
+    let mut deserializer = JsonDeserializer::from_cjson(merged);
+    let ret = T::deserialize(&mut deserializer, "");
+    deserializer.drop();
+    ret
+}
+
+/// Set the value at the JSON Pointer `pointer` of the serialized `value` and deserialize the result
+///
+/// Only paths that already exist in `T`'s serialized form can be set, any other
+/// pointer gives `NotFound`. A `new` value of the wrong type fails in the
+/// deserializer (`UnexpectedType`).
+#[cfg(feature = "osal_rs")]
+pub fn set_at_pointer<T>(value: &T, pointer: &str, new: &CJson) -> CJsonResult<T>
+where
+    T: Serialize + Deserialize
+{
+    use crate::de::JsonDeserializer;
+
+    let mut document = to_cjson(value)?;
+    let ret = JsonPointer::get_case_sensitive(&document, pointer)
+        .and_then(|_| new.duplicate(true))
+        .and_then(|new| {
+            // Dropping the operation frees `new`
+            let op = PatchOp::Replace { path: String::from(pointer), value: new };
+            JsonPatch::apply_op(&mut document, &op)
+        });
+    if let Err(e) = ret {
+        document.drop();
+        return Err(e);
+    }
+
+    let mut deserializer = JsonDeserializer::from_cjson(document);
+    let ret = T::deserialize(&mut deserializer, "");
+    deserializer.drop();
+    ret
 }
 
 #[cfg(feature = "osal_rs")]
@@ -137,3 +306,107 @@ where
     Ok(ret)
 }
 
+#[cfg(test)]
+mod tests {
+    use crate::CJson;
+    use crate::alloc_counter::measure;
+
+    #[test]
+    fn test_parse_print_baseline() {
+        let (_, stats) = measure(|| {
+            let json = CJson::parse(r#"{"version":1,"users":[1,2]}"#).unwrap();
+            let text = json.print_unformatted().unwrap();
+            json.drop();
+            text
+        });
+
+        // Parse: 5 items and 2 keys. Print: a working buffer, then an
+        // exact-size copy (custom hooks leave cJSON without realloc)
+        assert_eq!(stats.c_allocs, 9);
+        assert_eq!(stats.c_live, 0);
+        // Only the returned String survives on the Rust side
+        assert_eq!(stats.rust_allocs, 1);
+    }
+
+    #[test]
+    fn test_repeated_print_does_not_leak() {
+        let json = CJson::parse(r#"{"version":1,"users":[1,2]}"#).unwrap();
+        let (_, stats) = measure(|| {
+            for _ in 0..100 {
+                json.print().unwrap();
+                json.print_unformatted().unwrap();
+                json.printed_size(true).unwrap();
+            }
+        });
+        json.drop();
+
+        assert_eq!(stats.c_live, 0);
+        assert_eq!(stats.rust_live_bytes, 0);
+    }
+
+    #[cfg(feature = "osal_rs")]
+    #[test]
+    fn test_from_json_baseline() {
+        use crate::from_json;
+        use alloc::string::String;
+        use osal_rs_serde::Deserialize;
+
+        #[derive(Deserialize, Debug, Default)]
+        struct Config {
+            version: u8,
+            port: u16,
+        }
+
+        let json = String::from(r#"{"version":1,"port":80}"#);
+        let (config, first) = measure(|| from_json::<Config>(&json).unwrap());
+        let (_, second) = measure(|| from_json::<Config>(&json).unwrap());
+
+        assert_eq!(config.port, 80);
+        // The object, its 2 numbers and their 2 keys; the fields are read in
+        // place, nothing is duplicated
+        assert_eq!(first.c_allocs, 5);
+        // Every node is released and the count is stable between runs
+        assert_eq!(first.c_live, 0);
+        assert_eq!(first.rust_live_bytes, 0);
+        assert_eq!(first, second);
+    }
+
+    #[cfg(feature = "osal_rs")]
+    #[test]
+    fn test_serializer_and_deserializer_free_on_scope_exit() {
+        use crate::de::JsonDeserializer;
+        use crate::ser::JsonSerializer;
+        use osal_rs_serde::{Deserialize, Serialize};
+
+        #[derive(Serialize, Deserialize, Debug, Default)]
+        struct Ntp {
+            port: u16,
+        }
+
+        #[derive(Serialize, Deserialize, Debug, Default)]
+        struct Config {
+            version: u8,
+            ntp: Ntp,
+            ports: [u16; 2],
+        }
+
+        let config = Config { version: 1, ntp: Ntp { port: 123 }, ports: [80, 443] };
+        let (_, stats) = measure(|| {
+            // Neither is reset nor dropped by hand
+            let mut serializer = JsonSerializer::new();
+            config.serialize("", &mut serializer).unwrap();
+            let json = serializer.print_unformatted().unwrap();
+
+            let mut deserializer = JsonDeserializer::parse(&json).unwrap();
+            let decoded = Config::deserialize(&mut deserializer, "").unwrap();
+            assert_eq!(decoded.ntp.port, 123);
+
+            // An explicit drop followed by the scope exit frees nothing twice
+            let mut explicit = JsonDeserializer::parse(&json).unwrap();
+            explicit.drop();
+        });
+
+        // A double free would show up as a negative count
+        assert_eq!(stats.c_live, 0);
+    }
+}
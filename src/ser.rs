@@ -25,16 +25,108 @@ use osal_rs_serde::{Serialize, Serializer};
 use crate::CJsonResult;
 use crate::cjson::CJsonError;
 use crate::cjson::CJson;
+use crate::cjson::CJsonRef;
 
+use alloc::boxed::Box;
 use alloc::collections::BTreeMap;
 use alloc::vec::Vec;
 use alloc::string::String;
 use alloc::format;
 
 
+/// How `serialize_bytes` writes byte buffers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ByteEncoding {
+    /// Lowercase hex string, e.g. `"cafe"`
+    #[default]
+    Hex,
+    /// Array of numbers 0-255, e.g. `[202,254]`
+    Array,
+    /// Text string when the NUL-trimmed bytes are UTF-8, number array otherwise
+    Text,
+}
+
+/// Serialize a borrowed value exactly like the value itself.
+///
+/// A blanket `impl Serialize for &T` would break the orphan rule here (both the
+/// trait and `&T` are foreign), so borrowed fields are wrapped instead:
+/// `ByRef(&config)` produces the same JSON as `config`, including inside `Vec`.
+#[derive(Debug, Clone, Copy)]
+pub struct ByRef<'a, T>(pub &'a T);
+
+impl<T: Serialize> Serialize for ByRef<'_, T> {
+    fn serialize<S: Serializer>(&self, name: &str, serializer: &mut S) -> Result<(), S::Error> {
+        self.0.serialize(name, serializer)
+    }
+}
+
+/// Serialize a string-like value (`Cow<str>`, `Box<str>`, `Rc<str>`...) as a JSON string.
+///
+/// `Serialize` cannot be implemented for `Cow<str>` here for the same orphan-rule
+/// reason as `ByRef`, so such fields are wrapped: `AsStr(Cow::Borrowed("x"))`
+/// writes `"x"` through `serialize_str`, including inside `Vec`. Deserializing
+/// builds the value from an owned `String`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct AsStr<T>(pub T);
+
+impl<T: AsRef<str>> Serialize for AsStr<T> {
+    fn serialize<S: Serializer>(&self, name: &str, serializer: &mut S) -> Result<(), S::Error> {
+        serializer.serialize_str(name, self.0.as_ref())
+    }
+}
+
+/// Key type of a map written with `JsonSerializer::serialize_map`.
+///
+/// JSON keys are strings, so only strings and integers are supported:
+/// integers are written in decimal (`{"1":...}`) and parsed back by
+/// `JsonDeserializer::deserialize_map`.
+pub trait MapKey: Ord + Sized {
+    /// Append the key text to `out`
+    fn write_key(&self, out: &mut String);
+
+    /// Rebuild the key from its text
+    fn parse_key(key: &str) -> CJsonResult<Self>;
+}
+
+impl MapKey for String {
+    fn write_key(&self, out: &mut String) {
+        out.push_str(self);
+    }
+
+    fn parse_key(key: &str) -> CJsonResult<Self> {
+        Ok(String::from(key))
+    }
+}
+
+macro_rules! integer_map_key {
+    ($($t:ty),*) => {
+        $(
+            impl MapKey for $t {
+                fn write_key(&self, out: &mut String) {
+                    use core::fmt::Write;
+                    let _ = write!(out, "{}", self);
+                }
+
+                fn parse_key(key: &str) -> CJsonResult<Self> {
+                    key.parse().map_err(|_| CJsonError::UnexpectedType { expected: "integer key", found: "string" })
+                }
+            }
+        )*
+    };
+}
+
+integer_map_key!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize);
+
 pub struct JsonSerializer {
+    // Open containers. Apart from the root, each entry is an alias of a node
+    // already owned by its parent: a deep copy would be filled in while the
+    // parent kept the empty original, so never clone into the stack.
     stack: BTreeMap<String, CJson>,
     stack_name: Vec<String>,
+    byte_encoding: ByteEncoding,
+    skip_empty: bool,  // Drop containers that end up empty from their parent
+    skip_field: Option<Box<dyn FnMut(&str, &CJson) -> bool>>,
+    key_order: Vec<String>,  // Keys moved to the front of every object, in this order
 }
 
 
@@ -42,137 +134,65 @@ impl Serializer for JsonSerializer {
     type Error =  CJsonError;
 
     fn serialize_bool(&mut self, name: &str, v: bool) -> Result<(), Self::Error> {
-        let container = self.get_current_object()?;
-        if container.is_array() {
-            container.add_item_to_array(CJson::create_bool(v)?)?;
-        } else {
-            container.add_bool_to_object(name, v)?;
-        }
-        Ok(())
+        self.add_item(name, CJson::create_bool(v)?)
     }
 
 
     fn serialize_u8(&mut self, name: &str, v: u8) -> Result<(), Self::Error> {
-        let container = self.get_current_object()?;
-        if container.is_array() {
-            container.add_item_to_array(CJson::create_number(v as f64)?)?;
-        } else {
-            container.add_number_to_object(name, v as f64)?;
-        }
-        Ok(())
+        self.add_item(name, CJson::create_i64(i64::from(v))?)
     }
 
     fn serialize_i8(&mut self, name: &str, v: i8) -> Result<(), Self::Error> {
-        let container = self.get_current_object()?;
-        if container.is_array() {
-            container.add_item_to_array(CJson::create_number(v as f64)?)?;
-        } else {
-            container.add_number_to_object(name, v as f64)?;
-        }
-        Ok(())
+        self.add_item(name, CJson::create_i64(i64::from(v))?)
     }
 
     fn serialize_u16(&mut self, name: &str, v: u16) -> Result<(), Self::Error> {
-        let container = self.get_current_object()?;
-        if container.is_array() {
-            container.add_item_to_array(CJson::create_number(v as f64)?)?;
-        } else {
-            container.add_number_to_object(name, v as f64)?;
-        }
-        Ok(())
+        self.add_item(name, CJson::create_i64(i64::from(v))?)
     }
 
     fn serialize_i16(&mut self, name: &str, v: i16) -> Result<(), Self::Error> {
-        let container = self.get_current_object()?;
-        if container.is_array() {
-            container.add_item_to_array(CJson::create_number(v as f64)?)?;
-        } else {
-            container.add_number_to_object(name, v as f64)?;
-        }
-        Ok(())
+        self.add_item(name, CJson::create_i64(i64::from(v))?)
     }
 
     fn serialize_u32(&mut self, name: &str, v: u32) -> Result<(), Self::Error> {
-        let container = self.get_current_object()?;
-        if container.is_array() {
-            container.add_item_to_array(CJson::create_number(v as f64)?)?;
-        } else {
-            container.add_number_to_object(name, v as f64)?;
-        }
-        Ok(())
+        self.add_item(name, CJson::create_i64(i64::from(v))?)
     }
 
     fn serialize_i32(&mut self, name: &str, v: i32) -> Result<(), Self::Error> {
-        let container = self.get_current_object()?;
-        if container.is_array() {
-            container.add_item_to_array(CJson::create_number(v as f64)?)?;
-        } else {
-            container.add_number_to_object(name, v as f64)?;
-        }
-        Ok(())
+        self.add_item(name, CJson::create_i64(i64::from(v))?)
     }
 
     fn serialize_u64(&mut self, name: &str, v: u64) -> Result<(), Self::Error> {
-        let container = self.get_current_object()?;
-        if container.is_array() {
-            container.add_item_to_array(CJson::create_number(v as f64)?)?;
-        } else {
-            container.add_number_to_object(name, v as f64)?;
-        }
-        Ok(())
+        self.add_item(name, CJson::create_u64(v)?)
     }
 
     fn serialize_i64(&mut self, name: &str, v: i64) -> Result<(), Self::Error> {
-        let container = self.get_current_object()?;
-        if container.is_array() {
-            container.add_item_to_array(CJson::create_number(v as f64)?)?;
-        } else {
-            container.add_number_to_object(name, v as f64)?;
-        }
-        Ok(())
+        self.add_item(name, CJson::create_i64(v)?)
     }
 
     fn serialize_u128(&mut self, name: &str, v: u128) -> Result<(), Self::Error> {
-        let container = self.get_current_object()?;
-        if container.is_array() {
-            container.add_item_to_array(CJson::create_number(v as f64)?)?;
-        } else {
-            container.add_number_to_object(name, v as f64)?;
-        }
-        Ok(())
+        self.add_item(name, CJson::create_number(v as f64)?)
     }
 
     fn serialize_i128(&mut self, name: &str, v: i128) -> Result<(), Self::Error> {
-        let container = self.get_current_object()?;
-        if container.is_array() {
-            container.add_item_to_array(CJson::create_number(v as f64)?)?;
-        } else {
-            container.add_number_to_object(name, v as f64)?;
-        }
-        Ok(())
+        self.add_item(name, CJson::create_number(v as f64)?)
     }
 
     fn serialize_f32(&mut self, name: &str, v: f32) -> Result<(), Self::Error> {
-        let container = self.get_current_object()?;
-        if container.is_array() {
-            container.add_item_to_array(CJson::create_number(v as f64)?)?;
-        } else {
-            container.add_number_to_object(name, v as f64)?;
-        }
-        Ok(())
+        self.add_item(name, CJson::create_number(v as f64)?)
     }
 
     fn serialize_f64(&mut self, name: &str, v: f64) -> Result<(), Self::Error> {
-        let container = self.get_current_object()?;
-        if container.is_array() {
-            container.add_item_to_array(CJson::create_number(v)?)?;
-        } else {
-            container.add_number_to_object(name, v)?;
-        }
-        Ok(())
+        self.add_item(name, CJson::create_number(v)?)
     }
 
     fn serialize_bytes(&mut self, name: &str, v: &[u8]) -> Result<(), Self::Error> {
+        match self.byte_encoding {
+            ByteEncoding::Hex => {}
+            ByteEncoding::Array => return self.add_item(name, byte_array(v)?),
+            ByteEncoding::Text => return self.serialize_text_bytes(name, v),
+        }
+
         // Create a string for hex encoding
         let mut hex_string = String::new();
         for &byte in v {
@@ -180,47 +200,34 @@ impl Serializer for JsonSerializer {
             let _ = write!(&mut hex_string, "{:02x}", byte);
         }
 
-        let container = self.get_current_object()?;
-        if container.is_array() {
-            container.add_item_to_array(CJson::create_string(&hex_string)?)?;
-        } else {
-            container.add_string_to_object(name, &hex_string)?;
-        }
-        Ok(())
+        self.add_item(name, CJson::create_string(&hex_string)?)
     }
 
     fn serialize_string(&mut self, name: &str, v: &String) -> Result<(), Self::Error> {
-        let container = self.get_current_object()?;
-        if container.is_array() {
-            container.add_item_to_array(CJson::create_string(v)?)?;
-        } else {
-            container.add_string_to_object(name, v)?;
-        }
-        Ok(())
+        self.add_item(name, CJson::create_string(v)?)
     }
 
     fn serialize_str(&mut self, name: &str, v: &str) -> Result<(), Self::Error> {
-        let container = self.get_current_object()?;
-        if container.is_array() {
-            container.add_item_to_array(CJson::create_string(v)?)?;
-        } else {
-            container.add_string_to_object(name, v)?;
-        }
-        Ok(())
+        self.add_item(name, CJson::create_string(v)?)
     }
 
     fn serialize_vec<T>(&mut self, name: &str, v: &Vec<T>) -> Result<(), Self::Error>
     where
         T: Serialize {
+        if let Some(array) = scalar_array(v)? {
+            return self.add_item(name, array);
+        }
+
         // Create a JSON array
         let array = CJson::create_array()?;
+        let stacked = unsafe { array.alias() };
         
         // Add the array to the parent object
-        self.get_current_object()?.add_item_to_object(name, array.clone())?;
+        self.get_current_object()?.add_item_to_object(name, array)?;
         
-        // Push array onto stack
+        // Push array onto stack (same node the parent now owns)
         self.stack_name.push(String::from(name));
-        self.stack.insert(String::from(name), array);
+        self.stack.insert(String::from(name), stacked);
         
         // Serialize each item into the array
         for item in v.iter() {
@@ -229,6 +236,7 @@ impl Serializer for JsonSerializer {
         }
         
         // Pop array from stack
+        self.remove_if_empty()?;
         self.stack_name.pop();
         
         Ok(())
@@ -237,15 +245,25 @@ impl Serializer for JsonSerializer {
     fn serialize_array<T>(&mut self, name: &str, v: &[T]) -> Result<(), Self::Error>
     where
         T: Serialize {
+        // [u8; N] is a byte buffer, not a list of numbers
+        if let Some(bytes) = byte_elements(v) {
+            let bytes: Vec<u8> = bytes.collect();
+            return self.serialize_bytes(name, &bytes);
+        }
+        if let Some(array) = scalar_array(v)? {
+            return self.add_item(name, array);
+        }
+
         // Create a JSON array
         let array = CJson::create_array()?;
+        let stacked = unsafe { array.alias() };
         
         // Add the array to the parent object
-        self.get_current_object()?.add_item_to_object(name, array.clone())?;
+        self.get_current_object()?.add_item_to_object(name, array)?;
         
-        // Push array onto stack
+        // Push array onto stack (same node the parent now owns)
         self.stack_name.push(String::from(name));
-        self.stack.insert(String::from(name), array);
+        self.stack.insert(String::from(name), stacked);
         
         // Serialize each item into the array
         for item in v.iter() {
@@ -254,6 +272,7 @@ impl Serializer for JsonSerializer {
         }
         
         // Pop array from stack
+        self.remove_if_empty()?;
         self.stack_name.pop();
         
         Ok(())
@@ -268,20 +287,27 @@ impl Serializer for JsonSerializer {
                     if container.is_array() {
                         // We're serializing a struct that will be added to an array
                         let obj = CJson::create_object()?;
-                        container.add_item_to_array(obj.clone())?;
+                        let stacked = unsafe { obj.alias() };
+                        container.add_item_to_array(obj)?;
                         
                         // Use a unique key for this array element
                         let unique_key = format!("{}[{}]", last_name, container.get_array_size()? - 1);
                         self.stack_name.push(unique_key.clone());
-                        self.stack.insert(unique_key, obj);
+                        self.stack.insert(unique_key, stacked);
                         return Ok(());
                     }
                 }
             }
             
-            // Root object case
+            // Root object case, a root seeded by `with_root` is filled in place
+            match self.stack.get("") {
+                Some(root) if !root.is_object() => return Err(CJsonError::TypeError),
+                Some(_) => {}
+                None => {
+                    self.stack.insert(String::from(""), CJson::create_object()?);
+                }
+            }
             self.stack_name.push(String::from(""));
-            self.stack.insert(String::from(""), CJson::create_object()?);
 
             Ok(())
         } else {
@@ -297,9 +323,10 @@ impl Serializer for JsonSerializer {
             if let Some(phader_obj) = self.stack.get_mut(key) {
 
                 let obj = CJson::create_object()?;
-                phader_obj.add_item_to_object(name, obj.clone())?;
+                let stacked = unsafe { obj.alias() };
+                phader_obj.add_item_to_object(name, obj)?;
                 self.stack_name.push(String::from(name));
-                self.stack.insert(String::from(name), obj);
+                self.stack.insert(String::from(name), stacked);
                 Ok(())
             } else {
                 Err(CJsonError::InvalidOperation)
@@ -309,6 +336,13 @@ impl Serializer for JsonSerializer {
 
     fn serialize_struct_end(&mut self) -> Result<(), Self::Error> {
 
+        if !self.key_order.is_empty() {
+            let order = core::mem::take(&mut self.key_order);
+            let reordered = self.get_current_object().and_then(|object| reorder_keys(object, &order));
+            self.key_order = order;
+            reordered?;
+        }
+        self.remove_if_empty()?;
         self.stack_name.pop();
 
         Ok(())
@@ -326,33 +360,224 @@ impl JsonSerializer {
         Self {
             stack: BTreeMap::new(),
             stack_name: Vec::new(),
+            byte_encoding: ByteEncoding::Hex,
+            skip_empty: false,
+            skip_field: None,
+            key_order: Vec::new(),
         }
     }
 
-    pub fn print(&mut self) -> CJsonResult<String> {
+    /// Create a serializer that builds into `root` instead of a new object.
+    ///
+    /// A top-level struct adds its fields next to the members `root` already
+    /// holds. The serializer owns `root` until `take_root`, `take` or `reset`.
+    pub fn with_root(root: CJson) -> Self {
+        let mut serializer = Self::new();
+        serializer.stack.insert(String::from(""), root);
+        serializer
+    }
+
+    /// Serialize byte buffers (e.g. `Bytes<N>`) as their textual content
+    /// instead of hex.
+    ///
+    /// NUL padding is trimmed and valid UTF-8 is written as a JSON string;
+    /// anything else is written as an array of numbers.
+    pub fn set_bytes_as_text(&mut self, enable: bool) {
+        self.byte_encoding = if enable { ByteEncoding::Text } else { ByteEncoding::Hex };
+    }
+
+    /// Select how byte buffers are written (hex by default).
+    pub fn set_byte_encoding(&mut self, encoding: ByteEncoding) {
+        self.byte_encoding = encoding;
+    }
+
+    /// Omit objects and arrays that end up empty instead of writing `{}`/`[]`.
+    ///
+    /// Off by default; the root object is always kept.
+    pub fn set_skip_empty(&mut self, enable: bool) {
+        self.skip_empty = enable;
+    }
+
+    /// Omit object members for which `skip` returns `true`.
+    ///
+    /// `skip` receives the member name and its serialized value, before the
+    /// value is attached; objects and arrays are checked once complete.
+    /// Array elements and the root are never passed.
+    pub fn set_skip_field<F>(&mut self, skip: F)
+    where
+        F: FnMut(&str, &CJson) -> bool + 'static {
+        self.skip_field = Some(Box::new(skip));
+    }
+
+    /// Put the members named in `order` first in every object, in that order.
+    ///
+    /// Unlisted members follow in their original order. Applied as each object
+    /// ends, by detaching and re-adding its members. An empty `order` turns it off.
+    pub fn set_key_order(&mut self, order: &[&str]) {
+        self.key_order = order.iter().map(|key| String::from(*key)).collect();
+    }
+
+    /// Write an explicit JSON null for `name` (or a null element in an array).
+    ///
+    /// For schemas that require the key to be present even when the value is absent.
+    pub fn serialize_null(&mut self, name: &str) -> CJsonResult<()> {
+        self.add_item(name, CJson::create_null()?)
+    }
+
+    /// Embed a deep copy of a pre-built `value` under `name` (or as an array element).
+    ///
+    /// `value` stays owned by the caller.
+    pub fn serialize_cjson(&mut self, name: &str, value: &CJson) -> CJsonResult<()> {
+        self.add_item(name, value.duplicate(true)?)
+    }
+
+    /// Write `map` as a JSON object under `name` (or as an array element).
+    ///
+    /// Keys go through `MapKey`, e.g. `BTreeMap<u32, u8>` gives `{"1":10,"2":20}`.
+    pub fn serialize_map<K, V>(&mut self, name: &str, map: &BTreeMap<K, V>) -> CJsonResult<()>
+    where
+        K: MapKey,
+        V: Serialize {
+        self.serialize_struct_start(name, map.len())?;
+        let mut key = String::new();
+        for (k, v) in map {
+            key.clear();
+            k.write_key(&mut key);
+            v.serialize(&key, self)?;
+        }
+        self.serialize_struct_end()
+    }
 
-        if let Some(obj) = self.stack.first_entry() {
-            let obj = obj.get();
-            let ret = obj.print();
-            obj.drop();
-            ret
+    /// Print the serialized tree to a formatted string.
+    ///
+    /// The tree is left untouched, call `reset` to free it.
+    pub fn print(&self) -> CJsonResult<String> {
+        if let Some((_, obj)) = self.stack.first_key_value() {
+            obj.print()
         } else {
             Err(CJsonError::NotFound)
         }
-
     }
 
-    pub fn print_unformatted(&mut self) -> CJsonResult<String> {
-        if let Some(obj) = self.stack.first_entry() {
-            let obj = obj.get();
-            let ret = obj.print_unformatted();
-            obj.drop();
-            ret
+    /// Print the serialized tree to an unformatted string.
+    ///
+    /// The tree is left untouched, call `reset` to free it.
+    pub fn print_unformatted(&self) -> CJsonResult<String> {
+        if let Some((_, obj)) = self.stack.first_key_value() {
+            obj.print_unformatted()
         } else {
             Err(CJsonError::NotFound)
         }
     }
 
+    /// Take ownership of the serialized tree and clear the state.
+    ///
+    /// The caller must `drop` the returned tree.
+    pub fn take(&mut self) -> CJsonResult<CJson> {
+        let root = self.stack.remove("").ok_or(CJsonError::NotFound);
+        self.stack.clear();
+        self.stack_name.clear();
+        root
+    }
+
+    /// Consume the serializer and hand back the tree it built.
+    ///
+    /// The caller must `drop` the returned tree.
+    pub fn take_root(mut self) -> CJsonResult<CJson> {
+        self.take()
+    }
+
+    /// Free the serialized tree and clear the state so the serializer
+    /// can be reused for another top-level value.
+    pub fn reset(&mut self) {
+        // The root owns every other node in the stack
+        if let Some(root) = self.stack.remove("") {
+            root.drop();
+        }
+        self.stack.clear();
+        self.stack_name.clear();
+    }
+
+    fn serialize_text_bytes(&mut self, name: &str, v: &[u8]) -> CJsonResult<()> {
+        // Fixed-size buffers are NUL padded, drop the padding before checking for text
+        let end = v.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+        let item = match core::str::from_utf8(&v[..end]) {
+            Ok(text) if !text.contains('\0') => CJson::create_string(text)?,
+            _ => byte_array(v)?,
+        };
+        self.add_item(name, item)
+    }
+
+    /// Add a value to the current container, unless `skip_field` drops it.
+    ///
+    /// `item` is freed if it is not attached.
+    fn add_item(&mut self, name: &str, item: CJson) -> CJsonResult<()> {
+        // Kept to free the item if it could not be attached
+        let copy = unsafe { item.alias() };
+        let in_array = match self.get_current_object() {
+            Ok(container) => container.is_array(),
+            Err(e) => {
+                copy.drop();
+                return Err(e);
+            }
+        };
+        if !in_array && self.skips(name, &copy) {
+            copy.drop();
+            return Ok(());
+        }
+
+        let added = self.get_current_object().and_then(|container| {
+            if in_array {
+                container.add_item_to_array(item)
+            } else {
+                container.add_item_to_object(name, item)
+            }
+        });
+        if added.is_err() {
+            copy.drop();
+        }
+        added
+    }
+
+    /// Whether the `skip_field` predicate drops the object member `name`.
+    fn skips(&mut self, name: &str, node: &CJson) -> bool {
+        match self.skip_field.as_mut() {
+            Some(skip) => skip(name, node),
+            None => false,
+        }
+    }
+
+    /// Detach and free the container on top of the stack if it is empty
+    /// and `skip_empty` is set, or if it is an object member `skip_field` drops.
+    fn remove_if_empty(&mut self) -> CJsonResult<()> {
+        let len = self.stack_name.len();
+        if len < 2 {
+            return Ok(());
+        }
+
+        let name = self.stack_name[len - 1].clone();
+        let node = match self.stack.get(&name) {
+            Some(node) => unsafe { node.alias() },
+            None => return Err(CJsonError::InvalidOperation),
+        };
+        let empty = unsafe { (*node.as_ptr()).child }.is_null();
+        let in_object = self.stack.get(&self.stack_name[len - 2]).is_some_and(|parent| parent.is_object());
+        // The container is complete here, so the predicate sees its final content
+        let skipped = in_object && self.skips(&name, &node);
+        if !(skipped || (self.skip_empty && empty)) {
+            return Ok(());
+        }
+        let node = node.as_ptr() as *mut _;
+        let name = &name;
+
+        // Drop the stack alias first, the node is about to be freed
+        self.stack.remove(name);
+        let parent = self.stack.get_mut(&self.stack_name[len - 2]).ok_or(CJsonError::InvalidOperation)?;
+        let child = unsafe { CJsonRef::from_ptr(node) }?;
+        parent.detach_child(child)?.drop();
+        Ok(())
+    }
+
     fn get_current_object(&mut self) -> CJsonResult<&mut CJson> {
         if let Some(name) = self.stack_name.last() {
             if let Some(obj) = self.stack.get_mut(name) {
@@ -363,4 +588,213 @@ impl JsonSerializer {
 
         Err(CJsonError::InvalidOperation)
     }
-} 
\ No newline at end of file
+}
+
+impl Drop for JsonSerializer {
+    fn drop(&mut self) {
+        self.reset();
+    }
+}
+
+/// Move the members named in `order` to the front of `object`, keeping the
+/// others in their original order after them. Arrays are left untouched.
+fn reorder_keys(object: &mut CJson, order: &[String]) -> CJsonResult<()> {
+    if !object.is_object() {
+        return Ok(());
+    }
+    let mut entries: Vec<Option<(String, CJsonRef)>> = object.object_entries()?.into_iter().map(Some).collect();
+    let mut sorted = Vec::with_capacity(entries.len());
+    for key in order {
+        if let Some(entry) = entries.iter_mut().find(|entry| entry.as_ref().is_some_and(|(k, _)| k == key)) {
+            sorted.extend(entry.take());
+        }
+    }
+    sorted.extend(entries.into_iter().flatten());
+
+    // Re-appending every member in turn leaves them in the sorted order
+    for (key, member) in sorted {
+        let item = object.detach_child(member)?;
+        object.add_item_to_object(&key, item)?;
+    }
+    Ok(())
+}
+
+/// One number an element serialized to, as seen by [`ScalarProbe`]
+#[derive(Debug, Clone, Copy)]
+enum Scalar {
+    /// From `serialize_u8`, the element of a byte buffer
+    Byte(u8),
+    /// Any other number type that `f64` holds exactly
+    Number(f64),
+}
+
+impl Scalar {
+    fn number(self) -> f64 {
+        match self {
+            Scalar::Byte(byte) => f64::from(byte),
+            Scalar::Number(n) => n,
+        }
+    }
+}
+
+/// Serializer that records the single number a value writes and refuses
+/// everything else.
+///
+/// Without specialization this is how the array paths tell `[u8; N]` and
+/// `Vec<u32>` apart from other element types: the element's own `Serialize`
+/// impl says what it is, and no memory is ever reinterpreted.
+/// 64-bit integers may need Raw output (see `CJson::create_i64`), so they are refused.
+#[derive(Debug, Default)]
+struct ScalarProbe {
+    value: Option<Scalar>,
+}
+
+impl ScalarProbe {
+    fn record(&mut self, value: Scalar) -> CJsonResult<()> {
+        if self.value.replace(value).is_some() {
+            return Err(CJsonError::InvalidOperation);
+        }
+        Ok(())
+    }
+}
+
+impl Serializer for ScalarProbe {
+    type Error = CJsonError;
+
+    fn serialize_bool(&mut self, _name: &str, _v: bool) -> Result<(), Self::Error> {
+        Err(CJsonError::InvalidOperation)
+    }
+
+    fn serialize_u8(&mut self, _name: &str, v: u8) -> Result<(), Self::Error> {
+        self.record(Scalar::Byte(v))
+    }
+
+    fn serialize_i8(&mut self, _name: &str, v: i8) -> Result<(), Self::Error> {
+        self.record(Scalar::Number(f64::from(v)))
+    }
+
+    fn serialize_u16(&mut self, _name: &str, v: u16) -> Result<(), Self::Error> {
+        self.record(Scalar::Number(f64::from(v)))
+    }
+
+    fn serialize_i16(&mut self, _name: &str, v: i16) -> Result<(), Self::Error> {
+        self.record(Scalar::Number(f64::from(v)))
+    }
+
+    fn serialize_u32(&mut self, _name: &str, v: u32) -> Result<(), Self::Error> {
+        self.record(Scalar::Number(f64::from(v)))
+    }
+
+    fn serialize_i32(&mut self, _name: &str, v: i32) -> Result<(), Self::Error> {
+        self.record(Scalar::Number(f64::from(v)))
+    }
+
+    fn serialize_u64(&mut self, _name: &str, _v: u64) -> Result<(), Self::Error> {
+        Err(CJsonError::InvalidOperation)
+    }
+
+    fn serialize_i64(&mut self, _name: &str, _v: i64) -> Result<(), Self::Error> {
+        Err(CJsonError::InvalidOperation)
+    }
+
+    fn serialize_u128(&mut self, _name: &str, _v: u128) -> Result<(), Self::Error> {
+        Err(CJsonError::InvalidOperation)
+    }
+
+    fn serialize_i128(&mut self, _name: &str, _v: i128) -> Result<(), Self::Error> {
+        Err(CJsonError::InvalidOperation)
+    }
+
+    fn serialize_f32(&mut self, _name: &str, v: f32) -> Result<(), Self::Error> {
+        self.record(Scalar::Number(f64::from(v)))
+    }
+
+    fn serialize_f64(&mut self, _name: &str, v: f64) -> Result<(), Self::Error> {
+        self.record(Scalar::Number(v))
+    }
+
+    fn serialize_bytes(&mut self, _name: &str, _v: &[u8]) -> Result<(), Self::Error> {
+        Err(CJsonError::InvalidOperation)
+    }
+
+    fn serialize_string(&mut self, _name: &str, _v: &String) -> Result<(), Self::Error> {
+        Err(CJsonError::InvalidOperation)
+    }
+
+    fn serialize_str(&mut self, _name: &str, _v: &str) -> Result<(), Self::Error> {
+        Err(CJsonError::InvalidOperation)
+    }
+
+    fn serialize_vec<T>(&mut self, _name: &str, _v: &Vec<T>) -> Result<(), Self::Error>
+    where
+        T: Serialize {
+        Err(CJsonError::InvalidOperation)
+    }
+
+    fn serialize_array<T>(&mut self, _name: &str, _v: &[T]) -> Result<(), Self::Error>
+    where
+        T: Serialize {
+        Err(CJsonError::InvalidOperation)
+    }
+
+    fn serialize_struct_start(&mut self, _name: &str, _len: usize) -> Result<(), Self::Error> {
+        Err(CJsonError::InvalidOperation)
+    }
+
+    fn serialize_struct_end(&mut self) -> Result<(), Self::Error> {
+        Err(CJsonError::InvalidOperation)
+    }
+}
+
+/// The number `item` serializes to, if it is a single one `f64` holds exactly
+fn probe<T: Serialize>(item: &T) -> Option<Scalar> {
+    let mut probe = ScalarProbe::default();
+    item.serialize("", &mut probe).ok()?;
+    probe.value
+}
+
+/// The bytes of `v` when every element serializes with `serialize_u8`, as in `[u8; N]`.
+///
+/// An empty slice has no element to ask, so it gives `None` and stays an array.
+pub(crate) fn byte_elements<T: Serialize>(v: &[T]) -> Option<impl Iterator<Item = u8> + Clone + '_> {
+    if v.is_empty() || !v.iter().all(|item| matches!(probe(item), Some(Scalar::Byte(_)))) {
+        return None;
+    }
+    Some(v.iter().map(|item| match probe(item) {
+        Some(Scalar::Byte(byte)) => byte,
+        // Checked above, only an impl writing something else on a second call gets here
+        _ => 0,
+    }))
+}
+
+/// Build the whole array at once when every element is a number that `f64`
+/// holds exactly, skipping the per-element stack lookups of `Serialize`.
+///
+/// Gives `None` for other element types and for empty slices, which keep the
+/// generic path (and so `skip_empty`).
+fn scalar_array<T: Serialize>(v: &[T]) -> CJsonResult<Option<CJson>> {
+    if v.is_empty() {
+        return Ok(None);
+    }
+    let mut array = CJson::create_array()?;
+    for item in v {
+        let node = match probe(item) {
+            Some(value) => CJson::create_number(value.number()),
+            None => {
+                array.drop();
+                return Ok(None);
+            }
+        };
+        if let Err(e) = node.and_then(|node| array.add_item_to_array(node)) {
+            array.drop();
+            return Err(e);
+        }
+    }
+    Ok(Some(array))
+}
+
+/// Build a JSON array holding one number per byte
+fn byte_array(v: &[u8]) -> CJsonResult<CJson> {
+    let values: Vec<i32> = v.iter().map(|&b| b as i32).collect();
+    CJson::create_int_array(&values)
+}
@@ -0,0 +1,307 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+use core::fmt::Write;
+use core::result::Result;
+
+use osal_rs_serde::{Serialize, Serializer};
+
+use crate::CJsonResult;
+use crate::cjson::CJsonError;
+use crate::ser::{ByteEncoding, byte_elements};
+use crate::stream::{Level, text_len, write_number, write_string, write_text};
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+
+/// Caller-provided output buffer.
+struct SliceWriter<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl Write for SliceWriter<'_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let end = self.len + s.len();
+        if end > self.buf.len() {
+            return Err(core::fmt::Error);
+        }
+        self.buf[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+impl SliceWriter<'_> {
+    /// Run a write, reporting a full buffer as `CapacityExceeded`.
+    fn put(&mut self, write: impl FnOnce(&mut Self) -> core::fmt::Result) -> CJsonResult<()> {
+        write(self).map_err(|_| CJsonError::CapacityExceeded { capacity: self.buf.len() })
+    }
+}
+
+/// Serializer writing unformatted JSON into a caller-provided byte buffer.
+///
+/// Meant for targets without an allocator: nothing is allocated on the heap,
+/// open containers are tracked in a fixed stack of `DEPTH` levels. The output
+/// is the same text `JsonSerializer::print_unformatted` gives. A value that
+/// does not fit fails with `CapacityExceeded` and leaves a truncated document
+/// in the buffer; nesting deeper than `DEPTH` fails with `DepthLimitExceeded`.
+/// The root value must be a struct, anything else fails with `InvalidOperation`.
+pub struct SliceSerializer<'a, const DEPTH: usize = 16> {
+    writer: SliceWriter<'a>,
+    levels: [Level; DEPTH],
+    depth: usize,
+    byte_encoding: ByteEncoding,
+}
+
+impl<const DEPTH: usize> Serializer for SliceSerializer<'_, DEPTH> {
+    type Error = CJsonError;
+
+    fn serialize_bool(&mut self, name: &str, v: bool) -> Result<(), Self::Error> {
+        self.begin_value(name)?;
+        self.writer.put(|w| w.write_str(if v { "true" } else { "false" }))
+    }
+
+    fn serialize_u8(&mut self, name: &str, v: u8) -> Result<(), Self::Error> {
+        self.write_integer(name, v)
+    }
+
+    fn serialize_i8(&mut self, name: &str, v: i8) -> Result<(), Self::Error> {
+        self.write_integer(name, v)
+    }
+
+    fn serialize_u16(&mut self, name: &str, v: u16) -> Result<(), Self::Error> {
+        self.write_integer(name, v)
+    }
+
+    fn serialize_i16(&mut self, name: &str, v: i16) -> Result<(), Self::Error> {
+        self.write_integer(name, v)
+    }
+
+    fn serialize_u32(&mut self, name: &str, v: u32) -> Result<(), Self::Error> {
+        self.write_integer(name, v)
+    }
+
+    fn serialize_i32(&mut self, name: &str, v: i32) -> Result<(), Self::Error> {
+        self.write_integer(name, v)
+    }
+
+    fn serialize_u64(&mut self, name: &str, v: u64) -> Result<(), Self::Error> {
+        self.write_integer(name, v)
+    }
+
+    fn serialize_i64(&mut self, name: &str, v: i64) -> Result<(), Self::Error> {
+        self.write_integer(name, v)
+    }
+
+    fn serialize_u128(&mut self, name: &str, v: u128) -> Result<(), Self::Error> {
+        self.write_number(name, v as f64)
+    }
+
+    fn serialize_i128(&mut self, name: &str, v: i128) -> Result<(), Self::Error> {
+        self.write_number(name, v as f64)
+    }
+
+    fn serialize_f32(&mut self, name: &str, v: f32) -> Result<(), Self::Error> {
+        self.write_number(name, v as f64)
+    }
+
+    fn serialize_f64(&mut self, name: &str, v: f64) -> Result<(), Self::Error> {
+        self.write_number(name, v)
+    }
+
+    fn serialize_bytes(&mut self, name: &str, v: &[u8]) -> Result<(), Self::Error> {
+        self.write_bytes(name, v.iter().copied())
+    }
+
+    fn serialize_string(&mut self, name: &str, v: &String) -> Result<(), Self::Error> {
+        self.serialize_str(name, v)
+    }
+
+    fn serialize_str(&mut self, name: &str, v: &str) -> Result<(), Self::Error> {
+        self.begin_value(name)?;
+        self.writer.put(|w| write_string(w, v))
+    }
+
+    fn serialize_vec<T>(&mut self, name: &str, v: &Vec<T>) -> Result<(), Self::Error>
+    where
+        T: Serialize {
+        self.serialize_array(name, v.as_slice())
+    }
+
+    fn serialize_array<T>(&mut self, name: &str, v: &[T]) -> Result<(), Self::Error>
+    where
+        T: Serialize {
+        // [u8; N] is a byte buffer, not a list of numbers
+        if let Some(bytes) = byte_elements(v) {
+            return self.write_bytes(name, bytes);
+        }
+
+        self.open(name, true)?;
+        for item in v.iter() {
+            item.serialize("", self)?;
+        }
+        self.close()
+    }
+
+    fn serialize_struct_start(&mut self, name: &str, _len: usize) -> Result<(), Self::Error> {
+        if self.depth == 0 && name.is_empty() {
+            // Root object
+            self.push(false)?;
+            return self.writer.put(|w| w.write_char('{'));
+        }
+        self.open(name, false)
+    }
+
+    fn serialize_struct_end(&mut self) -> Result<(), Self::Error> {
+        self.close()
+    }
+}
+
+impl<'a, const DEPTH: usize> SliceSerializer<'a, DEPTH> {
+    /// Create a serializer writing into `buf`, from its start.
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self {
+            writer: SliceWriter { buf, len: 0 },
+            levels: [Level { array: false, empty: true }; DEPTH],
+            depth: 0,
+            byte_encoding: ByteEncoding::Hex,
+        }
+    }
+
+    /// Choose how byte buffers are written, see `JsonSerializer::set_byte_encoding`.
+    pub fn set_byte_encoding(&mut self, encoding: ByteEncoding) {
+        self.byte_encoding = encoding;
+    }
+
+    /// Bytes written so far.
+    pub fn len(&self) -> usize {
+        self.writer.len
+    }
+
+    /// Whether nothing was written yet.
+    pub fn is_empty(&self) -> bool {
+        self.writer.len == 0
+    }
+
+    /// Text written so far.
+    pub fn as_str(&self) -> &str {
+        // Only whole `&str`s are copied in, so this never fails
+        core::str::from_utf8(&self.writer.buf[..self.writer.len]).unwrap_or("")
+    }
+
+    /// Check the document is complete and give the number of bytes written.
+    ///
+    /// Returns `InvalidOperation` while a container is still open.
+    pub fn finish(&self) -> CJsonResult<usize> {
+        if self.depth != 0 {
+            return Err(CJsonError::InvalidOperation);
+        }
+        Ok(self.writer.len)
+    }
+
+    /// Write the separator and, inside an object, the key that precede a value.
+    fn begin_value(&mut self, name: &str) -> Result<(), CJsonError> {
+        let level = match self.depth.checked_sub(1) {
+            Some(top) => &mut self.levels[top],
+            None => return Err(CJsonError::InvalidOperation),
+        };
+        let first = level.empty;
+        let array = level.array;
+        level.empty = false;
+        self.writer.put(|w| {
+            if !first {
+                w.write_char(',')?;
+            }
+            if !array {
+                write_string(w, name)?;
+                w.write_char(':')?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Integers are exact, like `CJson::create_i64` output.
+    fn write_integer(&mut self, name: &str, v: impl core::fmt::Display) -> Result<(), CJsonError> {
+        self.begin_value(name)?;
+        self.writer.put(|w| write!(w, "{}", v))
+    }
+
+    fn write_number(&mut self, name: &str, v: f64) -> Result<(), CJsonError> {
+        self.begin_value(name)?;
+        self.writer.put(|w| write_number(w, v))
+    }
+
+    /// Write a byte buffer with the configured `ByteEncoding`; the bytes come
+    /// one at a time, so `[u8; N]` fields need no copy.
+    fn write_bytes(&mut self, name: &str, bytes: impl Iterator<Item = u8> + Clone) -> Result<(), CJsonError> {
+        match self.byte_encoding {
+            ByteEncoding::Hex => {
+                self.begin_value(name)?;
+                self.writer.put(|w| {
+                    w.write_char('"')?;
+                    for byte in bytes {
+                        write!(w, "{:02x}", byte)?;
+                    }
+                    w.write_char('"')
+                })
+            }
+            ByteEncoding::Array => self.write_byte_array(name, bytes),
+            ByteEncoding::Text => match text_len(bytes.clone()) {
+                Some(len) => {
+                    self.begin_value(name)?;
+                    self.writer.put(|w| write_text(w, bytes.take(len)))
+                }
+                None => self.write_byte_array(name, bytes),
+            },
+        }
+    }
+
+    fn write_byte_array(&mut self, name: &str, bytes: impl Iterator<Item = u8>) -> Result<(), CJsonError> {
+        self.open(name, true)?;
+        for byte in bytes {
+            self.write_integer("", byte)?;
+        }
+        self.close()
+    }
+
+    fn push(&mut self, array: bool) -> Result<(), CJsonError> {
+        if self.depth == DEPTH {
+            return Err(CJsonError::DepthLimitExceeded { max: DEPTH });
+        }
+        self.levels[self.depth] = Level { array, empty: true };
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn open(&mut self, name: &str, array: bool) -> Result<(), CJsonError> {
+        self.begin_value(name)?;
+        self.push(array)?;
+        self.writer.put(|w| w.write_char(if array { '[' } else { '{' }))
+    }
+
+    fn close(&mut self) -> Result<(), CJsonError> {
+        let top = self.depth.checked_sub(1).ok_or(CJsonError::InvalidOperation)?;
+        self.depth = top;
+        let array = self.levels[top].array;
+        self.writer.put(|w| w.write_char(if array { ']' } else { '}' }))
+    }
+}
@@ -0,0 +1,409 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+use core::fmt::Write;
+use core::result::Result;
+
+use osal_rs_serde::{Serialize, Serializer};
+
+use crate::cjson::CJsonError;
+use crate::ser::{ByteEncoding, byte_elements};
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+
+/// An open array or object.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Level {
+    pub(crate) array: bool,
+    pub(crate) empty: bool,
+}
+
+/// Serializer writing unformatted JSON straight to a `core::fmt::Write` sink.
+///
+/// The output is the same text `JsonSerializer::print_unformatted` gives, but no
+/// cJSON tree is built. What was written before a failing call stays in the sink.
+/// Containers cannot be taken back once opened, so there is no `skip_empty`.
+pub struct StreamSerializer<W: Write> {
+    writer: W,
+    levels: Vec<Level>,
+    byte_encoding: ByteEncoding,
+}
+
+impl<W: Write> Serializer for StreamSerializer<W> {
+    type Error = CJsonError;
+
+    fn serialize_bool(&mut self, name: &str, v: bool) -> Result<(), Self::Error> {
+        self.begin_value(name)?;
+        self.writer.write_str(if v { "true" } else { "false" })?;
+        Ok(())
+    }
+
+    fn serialize_u8(&mut self, name: &str, v: u8) -> Result<(), Self::Error> {
+        self.write_number(name, v as f64)
+    }
+
+    fn serialize_i8(&mut self, name: &str, v: i8) -> Result<(), Self::Error> {
+        self.write_number(name, v as f64)
+    }
+
+    fn serialize_u16(&mut self, name: &str, v: u16) -> Result<(), Self::Error> {
+        self.write_number(name, v as f64)
+    }
+
+    fn serialize_i16(&mut self, name: &str, v: i16) -> Result<(), Self::Error> {
+        self.write_number(name, v as f64)
+    }
+
+    fn serialize_u32(&mut self, name: &str, v: u32) -> Result<(), Self::Error> {
+        self.write_number(name, v as f64)
+    }
+
+    fn serialize_i32(&mut self, name: &str, v: i32) -> Result<(), Self::Error> {
+        self.write_number(name, v as f64)
+    }
+
+    fn serialize_u64(&mut self, name: &str, v: u64) -> Result<(), Self::Error> {
+        self.write_integer(name, v)
+    }
+
+    fn serialize_i64(&mut self, name: &str, v: i64) -> Result<(), Self::Error> {
+        self.write_integer(name, v)
+    }
+
+    fn serialize_u128(&mut self, name: &str, v: u128) -> Result<(), Self::Error> {
+        self.write_number(name, v as f64)
+    }
+
+    fn serialize_i128(&mut self, name: &str, v: i128) -> Result<(), Self::Error> {
+        self.write_number(name, v as f64)
+    }
+
+    fn serialize_f32(&mut self, name: &str, v: f32) -> Result<(), Self::Error> {
+        self.write_number(name, v as f64)
+    }
+
+    fn serialize_f64(&mut self, name: &str, v: f64) -> Result<(), Self::Error> {
+        self.write_number(name, v)
+    }
+
+    fn serialize_bytes(&mut self, name: &str, v: &[u8]) -> Result<(), Self::Error> {
+        self.write_bytes(name, v.iter().copied())
+    }
+
+    fn serialize_string(&mut self, name: &str, v: &String) -> Result<(), Self::Error> {
+        self.serialize_str(name, v)
+    }
+
+    fn serialize_str(&mut self, name: &str, v: &str) -> Result<(), Self::Error> {
+        self.begin_value(name)?;
+        write_string(&mut self.writer, v)?;
+        Ok(())
+    }
+
+    fn serialize_vec<T>(&mut self, name: &str, v: &Vec<T>) -> Result<(), Self::Error>
+    where
+        T: Serialize {
+        self.serialize_array(name, v.as_slice())
+    }
+
+    fn serialize_array<T>(&mut self, name: &str, v: &[T]) -> Result<(), Self::Error>
+    where
+        T: Serialize {
+        // [u8; N] is a byte buffer, not a list of numbers
+        if let Some(bytes) = byte_elements(v) {
+            return self.write_bytes(name, bytes);
+        }
+
+        self.open(name, true)?;
+        for item in v.iter() {
+            item.serialize("", self)?;
+        }
+        self.close()
+    }
+
+    fn serialize_struct_start(&mut self, name: &str, _len: usize) -> Result<(), Self::Error> {
+        if self.levels.is_empty() && name.is_empty() {
+            // Root object
+            self.writer.write_char('{')?;
+            self.levels.push(Level { array: false, empty: true });
+            return Ok(());
+        }
+        self.open(name, false)
+    }
+
+    fn serialize_struct_end(&mut self) -> Result<(), Self::Error> {
+        self.close()
+    }
+}
+
+impl<W: Write> StreamSerializer<W> {
+    /// Create a serializer writing to `writer`.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            levels: Vec::new(),
+            byte_encoding: ByteEncoding::Hex,
+        }
+    }
+
+    /// Choose how byte buffers are written, see `JsonSerializer::set_byte_encoding`.
+    pub fn set_byte_encoding(&mut self, encoding: ByteEncoding) {
+        self.byte_encoding = encoding;
+    }
+
+    /// Give back the sink.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Write the separator and, inside an object, the key that precede a value.
+    fn begin_value(&mut self, name: &str) -> Result<(), CJsonError> {
+        let level = self.levels.last_mut().ok_or(CJsonError::InvalidOperation)?;
+        if !level.empty {
+            self.writer.write_char(',')?;
+        }
+        level.empty = false;
+        if !level.array {
+            write_string(&mut self.writer, name)?;
+            self.writer.write_char(':')?;
+        }
+        Ok(())
+    }
+
+    /// 64-bit integers are exact, like `CJson::create_i64` output.
+    fn write_integer(&mut self, name: &str, v: impl core::fmt::Display) -> Result<(), CJsonError> {
+        self.begin_value(name)?;
+        write!(self.writer, "{}", v)?;
+        Ok(())
+    }
+
+    fn write_number(&mut self, name: &str, v: f64) -> Result<(), CJsonError> {
+        self.begin_value(name)?;
+        write_number(&mut self.writer, v)?;
+        Ok(())
+    }
+
+    /// Write a byte buffer with the configured `ByteEncoding`; the bytes come
+    /// one at a time, so `[u8; N]` fields need no copy.
+    fn write_bytes(&mut self, name: &str, bytes: impl Iterator<Item = u8> + Clone) -> Result<(), CJsonError> {
+        match self.byte_encoding {
+            ByteEncoding::Hex => {
+                self.begin_value(name)?;
+                self.writer.write_char('"')?;
+                for byte in bytes {
+                    write!(self.writer, "{:02x}", byte)?;
+                }
+                self.writer.write_char('"')?;
+                Ok(())
+            }
+            ByteEncoding::Array => self.write_byte_array(name, bytes),
+            ByteEncoding::Text => match text_len(bytes.clone()) {
+                Some(len) => {
+                    self.begin_value(name)?;
+                    write_text(&mut self.writer, bytes.take(len))?;
+                    Ok(())
+                }
+                None => self.write_byte_array(name, bytes),
+            },
+        }
+    }
+
+    fn write_byte_array(&mut self, name: &str, bytes: impl Iterator<Item = u8>) -> Result<(), CJsonError> {
+        self.open(name, true)?;
+        for byte in bytes {
+            self.write_number("", byte as f64)?;
+        }
+        self.close()
+    }
+
+    fn open(&mut self, name: &str, array: bool) -> Result<(), CJsonError> {
+        self.begin_value(name)?;
+        self.writer.write_char(if array { '[' } else { '{' })?;
+        self.levels.push(Level { array, empty: true });
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<(), CJsonError> {
+        let level = self.levels.pop().ok_or(CJsonError::InvalidOperation)?;
+        self.writer.write_char(if level.array { ']' } else { '}' })?;
+        Ok(())
+    }
+}
+
+/// Write a quoted string escaped the way cJSON prints it.
+pub(crate) fn write_string<W: Write>(w: &mut W, v: &str) -> core::fmt::Result {
+    w.write_char('"')?;
+    for c in v.chars() {
+        write_escaped(w, c)?;
+    }
+    w.write_char('"')
+}
+
+fn write_escaped<W: Write>(w: &mut W, c: char) -> core::fmt::Result {
+    match c {
+        '"' => w.write_str("\\\""),
+        '\\' => w.write_str("\\\\"),
+        '\u{08}' => w.write_str("\\b"),
+        '\u{0c}' => w.write_str("\\f"),
+        '\n' => w.write_str("\\n"),
+        '\r' => w.write_str("\\r"),
+        '\t' => w.write_str("\\t"),
+        c if (c as u32) < 0x20 => write!(w, "\\u{:04x}", c as u32),
+        c => w.write_char(c),
+    }
+}
+
+/// Length of the text NUL-padded `bytes` hold, the padding excluded, or `None`
+/// when that text is not UTF-8 or holds a NUL of its own. Same rule as
+/// `JsonSerializer` with `ByteEncoding::Text`, without needing a slice.
+pub(crate) fn text_len(mut bytes: impl Iterator<Item = u8>) -> Option<usize> {
+    let (mut pos, mut end, mut padding) = (0, 0, false);
+    while let Some(decoded) = next_char(&mut bytes) {
+        let (c, width) = decoded?;
+        pos += width;
+        if c == '\0' {
+            padding = true;
+        } else if padding {
+            return None;
+        } else {
+            end = pos;
+        }
+    }
+    Some(end)
+}
+
+/// Write the UTF-8 `bytes` as a quoted string; check them with `text_len` first.
+pub(crate) fn write_text<W: Write>(w: &mut W, mut bytes: impl Iterator<Item = u8>) -> core::fmt::Result {
+    w.write_char('"')?;
+    while let Some(Some((c, _))) = next_char(&mut bytes) {
+        write_escaped(w, c)?;
+    }
+    w.write_char('"')
+}
+
+/// Decode the next `char` and its width in bytes; `Some(None)` for invalid UTF-8.
+fn next_char(bytes: &mut impl Iterator<Item = u8>) -> Option<Option<(char, usize)>> {
+    let lead = bytes.next()?;
+    let width = match lead {
+        0x00..=0x7f => 1,
+        0xc2..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf4 => 4,
+        _ => return Some(None),
+    };
+    let mut buf = [lead, 0, 0, 0];
+    for slot in &mut buf[1..width] {
+        match bytes.next() {
+            Some(byte) => *slot = byte,
+            None => return Some(None),
+        }
+    }
+    Some(core::str::from_utf8(&buf[..width]).ok().and_then(|text| text.chars().next()).map(|c| (c, width)))
+}
+
+/// Write a number the way cJSON prints it: as an integer when it is one that
+/// fits a C `int`, otherwise `%1.15g`, or `%1.17g` if that does not read back.
+pub(crate) fn write_number<W: Write>(w: &mut W, v: f64) -> core::fmt::Result {
+    if !v.is_finite() {
+        return w.write_str("null");
+    }
+    // cJSON compares with its int copy of the value, saturated to the int range
+    let int = if v >= i32::MAX as f64 {
+        i32::MAX
+    } else if v <= i32::MIN as f64 {
+        i32::MIN
+    } else {
+        v as i32
+    };
+    if v == int as f64 {
+        return write!(w, "{}", int);
+    }
+
+    let short = format_g(v, 15);
+    let reads_back = match short.as_str().parse::<f64>() {
+        Ok(back) => (back - v).abs() <= back.abs().max(v.abs()) * f64::EPSILON,
+        Err(_) => false,
+    };
+    if reads_back {
+        w.write_str(short.as_str())
+    } else {
+        w.write_str(format_g(v, 17).as_str())
+    }
+}
+
+/// C's `%.{precision}g` for a finite value, formatted on the stack.
+fn format_g(v: f64, precision: usize) -> NumberText {
+    let mut sci = NumberText::new();
+    let _ = write!(sci, "{:.*e}", precision - 1, v);
+    let (mantissa, exp) = match sci.as_str().split_once('e') {
+        Some(parts) => parts,
+        None => return sci,
+    };
+    let exp: i32 = exp.parse().unwrap_or(0);
+    let mut out = NumberText::new();
+    if exp < -4 || exp >= precision as i32 {
+        let _ = write!(out, "{}e{}{:02}", trim_fraction(mantissa), if exp < 0 { '-' } else { '+' }, exp.unsigned_abs());
+    } else {
+        let _ = write!(out, "{:.*}", (precision as i32 - 1 - exp) as usize, v);
+        // Trimming only shortens the text from its end
+        out.len = trim_fraction(out.as_str()).len();
+    }
+    out
+}
+
+/// Fixed buffer holding one formatted number; `%.17g` needs at most 24 bytes.
+struct NumberText {
+    bytes: [u8; 32],
+    len: usize,
+}
+
+impl NumberText {
+    fn new() -> Self {
+        Self { bytes: [0; 32], len: 0 }
+    }
+
+    fn as_str(&self) -> &str {
+        // Only whole `&str`s are appended, so this never fails
+        core::str::from_utf8(&self.bytes[..self.len]).unwrap_or("")
+    }
+}
+
+impl Write for NumberText {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let end = self.len + s.len();
+        if end > self.bytes.len() {
+            return Err(core::fmt::Error);
+        }
+        self.bytes[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+/// Drop trailing zeros of a fraction, and the point if nothing is left after it.
+fn trim_fraction(text: &str) -> &str {
+    if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        text
+    }
+}
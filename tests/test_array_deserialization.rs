@@ -155,3 +155,28 @@ fn test_roundtrip_serialization_with_arrays() {
     assert_eq!(deserialized.ntp.server.as_str(), "pool.ntp.org");
     assert_eq!(deserialized.ntp.enabled, true);
 }
+
+#[test]
+fn test_array_length_mismatch() {
+    use cjson_binding::CJsonError;
+    use cjson_binding::de::JsonDeserializer;
+    use osal_rs_serde::Deserializer;
+
+    // Too few elements
+    let mut deserializer = JsonDeserializer::parse(r#"{"values":[1,2]}"#).expect("Failed to parse");
+    let ret: Result<[u32; 3], _> = deserializer.deserialize_array("values");
+    assert_eq!(ret, Err(CJsonError::ArrayLengthMismatch { expected: 3, actual: 2 }));
+    deserializer.drop();
+
+    // Too many elements
+    let mut deserializer = JsonDeserializer::parse(r#"{"values":[1,2,3,4]}"#).expect("Failed to parse");
+    let ret: Result<[u32; 3], _> = deserializer.deserialize_array("values");
+    assert_eq!(ret, Err(CJsonError::ArrayLengthMismatch { expected: 3, actual: 4 }));
+    deserializer.drop();
+
+    // Exact length
+    let mut deserializer = JsonDeserializer::parse(r#"{"values":[1,2,3]}"#).expect("Failed to parse");
+    let ret: Result<[u32; 3], _> = deserializer.deserialize_array("values");
+    assert_eq!(ret, Ok([1, 2, 3]));
+    deserializer.drop();
+}
@@ -0,0 +1,65 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for Array Deserialization Error Cleanup
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ * This test verifies that elements already deserialized are released
+ * when a later element of the same array fails.
+ *
+ ***************************************************************************/
+
+#[path = "../src/alloc_counter.rs"]
+mod alloc_counter;
+
+use alloc_counter::measure;
+use cjson_binding::de::JsonDeserializer;
+use osal_rs_serde::{Deserialize, Deserializer};
+
+#[derive(Deserialize, Debug, Default)]
+struct Item {
+    tags: Vec<u8>,
+    value: u8,
+}
+
+#[test]
+fn test_failed_element_releases_previous_elements() {
+    let json = r#"{
+        "good":[{"tags":[1,2,3],"value":1},{"tags":[4,5,6],"value":2}],
+        "bad":[{"tags":[1,2,3],"value":1},{"tags":[4,5,6],"value":300}]
+    }"#;
+
+    let mut deserializer = JsonDeserializer::parse(json).expect("Failed to parse");
+
+    // Warm up so the deserializer's own bookkeeping reaches a steady capacity
+    let good: Vec<Item> = deserializer.deserialize_vec("good").expect("Failed to deserialize");
+    assert_eq!(good.len(), 2);
+    drop(good);
+
+    let (failed, stats) = measure(|| {
+        let bad: Result<Vec<Item>, _> = deserializer.deserialize_vec("bad");
+        bad.is_err()
+    });
+    assert!(failed);
+
+    assert_eq!(stats.rust_live_bytes, 0, "Elements deserialized before the failure were leaked");
+    assert_eq!(stats.c_live, 0, "Element copies were leaked");
+
+    // The deserializer is still usable after the failure
+    let good: Vec<Item> = deserializer.deserialize_vec("good").expect("Failed to deserialize");
+    assert_eq!(good[1].tags, vec![4, 5, 6]);
+
+    deserializer.drop();
+}
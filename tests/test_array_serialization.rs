@@ -19,7 +19,8 @@
  ***************************************************************************/
 
 use cjson_binding::{to_json, from_json};
-use osal_rs_serde::{Serialize, Deserialize};
+use cjson_binding::ser::JsonSerializer;
+use osal_rs_serde::{Serialize, Deserialize, Serializer};
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
 struct UserConfig {
@@ -68,6 +69,25 @@ fn test_array_serialization() {
     assert_eq!(deserialized.users[1].password, 400);
 }
 
+#[test]
+fn test_array_of_structs_is_populated() {
+    let config = Config {
+        version: 2,
+        users: [
+            UserConfig { user: 1, password: 2 },
+            UserConfig { user: 3, password: 4 },
+        ],
+    };
+
+    let json_str = to_json(&config).expect("Failed to serialize");
+
+    // Both elements must be filled in the tree owned by the parent object
+    assert_eq!(
+        json_str,
+        r#"{"version":2,"users":[{"user":1,"password":2},{"user":3,"password":4}]}"#
+    );
+}
+
 #[test]
 fn test_empty_array_serialization() {
     #[derive(Serialize, Deserialize, Debug, PartialEq)]
@@ -85,3 +105,23 @@ fn test_empty_array_serialization() {
     let deserialized: EmptyArrayConfig = from_json(&json_str).expect("Failed to deserialize");
     assert_eq!(deserialized, config);
 }
+
+#[test]
+fn test_slice_of_structs_serialization() {
+    let users: Vec<UserConfig> = (1..=4)
+        .map(|i| UserConfig { user: i * 100, password: i })
+        .collect();
+    // A runtime-length slice, not a fixed-size array
+    let slice: &[UserConfig] = &users[1..];
+
+    let mut serializer = JsonSerializer::new();
+    serializer.serialize_struct_start("", 1).expect("Failed to start struct");
+    serializer.serialize_array("users", slice).expect("Failed to serialize slice");
+    serializer.serialize_struct_end().expect("Failed to end struct");
+    let json_str = serializer.print_unformatted().expect("Failed to print");
+
+    assert_eq!(
+        json_str,
+        r#"{"users":[{"user":200,"password":2},{"user":300,"password":3},{"user":400,"password":4}]}"#
+    );
+}
@@ -0,0 +1,77 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for String-like Fields
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+use std::borrow::Cow;
+
+use cjson_binding::de::JsonDeserializer;
+use cjson_binding::ser::{AsStr, JsonSerializer};
+use osal_rs_serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Device {
+    name: AsStr<Cow<'static, str>>,
+    tags: Vec<AsStr<Cow<'static, str>>>,
+}
+
+fn round_trip(device: &Device) -> Device {
+    let mut serializer = JsonSerializer::new();
+    device.serialize("", &mut serializer).expect("Failed to serialize");
+    let json = serializer.print_unformatted().expect("Failed to print");
+    assert_eq!(json, r#"{"name":"garden","tags":["outdoor","wifi"]}"#);
+
+    let mut deserializer = JsonDeserializer::parse(&json).expect("Failed to parse");
+    let decoded = Device::deserialize(&mut deserializer, "").expect("Failed to deserialize");
+    deserializer.drop();
+    decoded
+}
+
+#[test]
+fn test_borrowed_cow_round_trip() {
+    let device = Device {
+        name: AsStr(Cow::Borrowed("garden")),
+        tags: vec![AsStr(Cow::Borrowed("outdoor")), AsStr(Cow::Borrowed("wifi"))],
+    };
+
+    let decoded = round_trip(&device);
+    assert_eq!(decoded, device);
+    assert!(matches!(decoded.name.0, Cow::Owned(_)));
+}
+
+#[test]
+fn test_owned_cow_round_trip() {
+    let device = Device {
+        name: AsStr(Cow::Owned(String::from("garden"))),
+        tags: vec![AsStr(Cow::Owned(String::from("outdoor"))), AsStr(Cow::Borrowed("wifi"))],
+    };
+
+    assert_eq!(round_trip(&device), device);
+}
+
+#[test]
+fn test_other_string_likes() {
+    let mut serializer = JsonSerializer::new();
+    serializer.serialize_struct_start("", 2).expect("Failed to start struct");
+    AsStr(Box::<str>::from("boxed")).serialize("boxed", &mut serializer).expect("Failed to serialize");
+    AsStr(std::rc::Rc::<str>::from("shared")).serialize("shared", &mut serializer).expect("Failed to serialize");
+    serializer.serialize_struct_end().expect("Failed to end struct");
+    let json = serializer.print_unformatted().expect("Failed to print");
+
+    assert_eq!(json, r#"{"boxed":"boxed","shared":"shared"}"#);
+}
@@ -0,0 +1,62 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for Lenient Bool Deserialization
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+use cjson_binding::CJsonError;
+use cjson_binding::de::JsonDeserializer;
+use osal_rs_serde::Deserializer;
+
+fn read_flag(json: &str, coerce: bool) -> Result<bool, CJsonError> {
+    let mut deserializer = JsonDeserializer::parse(json).expect("Failed to parse");
+    deserializer.set_coercion(coerce);
+    let ret = deserializer.deserialize_bool("flag");
+    deserializer.drop();
+    ret
+}
+
+fn unexpected(found: &'static str) -> CJsonError {
+    CJsonError::UnexpectedType { expected: "bool", found }
+}
+
+#[test]
+fn test_bool_coercion_accepted_forms() {
+    assert_eq!(read_flag(r#"{"flag":true}"#, true), Ok(true));
+    assert_eq!(read_flag(r#"{"flag":false}"#, true), Ok(false));
+    assert_eq!(read_flag(r#"{"flag":1}"#, true), Ok(true));
+    assert_eq!(read_flag(r#"{"flag":0}"#, true), Ok(false));
+    assert_eq!(read_flag(r#"{"flag":"true"}"#, true), Ok(true));
+    assert_eq!(read_flag(r#"{"flag":"TRUE"}"#, true), Ok(true));
+    assert_eq!(read_flag(r#"{"flag":"False"}"#, true), Ok(false));
+    assert_eq!(read_flag(r#"{"flag":"1"}"#, true), Ok(true));
+    assert_eq!(read_flag(r#"{"flag":"0"}"#, true), Ok(false));
+}
+
+#[test]
+fn test_bool_coercion_rejected_forms() {
+    assert_eq!(read_flag(r#"{"flag":2}"#, true), Err(unexpected("number")));
+    assert_eq!(read_flag(r#"{"flag":"yes"}"#, true), Err(unexpected("string")));
+    assert_eq!(read_flag(r#"{"flag":null}"#, true), Err(unexpected("null")));
+}
+
+#[test]
+fn test_bool_strict_by_default() {
+    assert_eq!(read_flag(r#"{"flag":true}"#, false), Ok(true));
+    assert_eq!(read_flag(r#"{"flag":1}"#, false), Err(unexpected("number")));
+    assert_eq!(read_flag(r#"{"flag":"true"}"#, false), Err(unexpected("string")));
+}
@@ -0,0 +1,79 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for Fixed-Size Byte Arrays
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+use cjson_binding::de::JsonDeserializer;
+use cjson_binding::ser::{ByteEncoding, JsonSerializer};
+use cjson_binding::CJsonError;
+use osal_rs_serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+struct Device {
+    id: u32,
+    key: [u8; 16],
+}
+
+#[test]
+fn test_byte_array_hex_roundtrip() {
+    let device = Device {
+        id: 7,
+        key: [
+            0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77,
+            0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff,
+        ],
+    };
+
+    let mut serializer = JsonSerializer::new();
+    serializer.set_byte_encoding(ByteEncoding::Hex);
+    device.serialize("", &mut serializer).expect("Failed to serialize");
+    let json = serializer.print_unformatted().expect("Failed to print");
+    serializer.reset();
+
+    assert_eq!(json, r#"{"id":7,"key":"00112233445566778899aabbccddeeff"}"#);
+
+    let mut deserializer = JsonDeserializer::parse(&json).expect("Failed to parse");
+    let decoded = Device::deserialize(&mut deserializer, "").expect("Failed to deserialize");
+    deserializer.drop();
+
+    assert_eq!(decoded.key, device.key);
+    assert_eq!(decoded, device);
+}
+
+#[derive(Deserialize, Debug, Default, PartialEq)]
+struct Flags {
+    enabled: [bool; 2],
+}
+
+#[derive(Deserialize, Debug, Default, PartialEq)]
+struct Offsets {
+    delta: [i8; 2],
+}
+
+#[test]
+fn test_non_byte_array_from_string_expects_array() {
+    let mut deserializer = JsonDeserializer::parse(r#"{"enabled":"0001"}"#).expect("Failed to parse");
+    let result = Flags::deserialize(&mut deserializer, "");
+    deserializer.drop();
+    assert_eq!(result, Err(CJsonError::UnexpectedType { expected: "array", found: "string" }));
+
+    let mut deserializer = JsonDeserializer::parse(r#"{"delta":"00ff"}"#).expect("Failed to parse");
+    let result = Offsets::deserialize(&mut deserializer, "");
+    deserializer.drop();
+    assert_eq!(result, Err(CJsonError::UnexpectedType { expected: "array", found: "string" }));
+}
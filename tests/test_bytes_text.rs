@@ -0,0 +1,113 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for Text Byte Buffers
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+use cjson_binding::de::JsonDeserializer;
+use cjson_binding::ser::{ByteEncoding, JsonSerializer};
+use osal_rs::utils::Bytes;
+use osal_rs_serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+struct WifiConfig {
+    ssid: Bytes<32>,
+    hostname: Bytes<32>,
+}
+
+#[test]
+fn test_text_bytes_roundtrip() {
+    let wifi = WifiConfig {
+        ssid: Bytes::new_by_str(""),
+        hostname: Bytes::new_by_str("hi-happy-garden-rs"),
+    };
+
+    let mut serializer = JsonSerializer::new();
+    serializer.set_bytes_as_text(true);
+    wifi.serialize("", &mut serializer).expect("Failed to serialize");
+    let json = serializer.print_unformatted().expect("Failed to print");
+
+    assert!(json.contains(r#""ssid":"""#));
+    assert!(json.contains(r#""hostname":"hi-happy-garden-rs""#));
+
+    let mut deserializer = JsonDeserializer::parse(&json).expect("Failed to parse");
+    deserializer.set_bytes_as_text(true);
+    let decoded = WifiConfig::deserialize(&mut deserializer, "").expect("Failed to deserialize");
+    deserializer.drop();
+
+    assert_eq!(decoded.ssid.as_str(), "");
+    assert_eq!(decoded.hostname.as_str(), "hi-happy-garden-rs");
+}
+
+#[test]
+fn test_hex_looking_text_is_not_decoded() {
+    let mut deserializer = JsonDeserializer::parse(r#"{"name":"cafe"}"#).expect("Failed to parse");
+    deserializer.set_bytes_as_text(true);
+    let mut buffer = [0u8; 32];
+    let len = deserializer.deserialize_bytes("name", &mut buffer).expect("Failed to deserialize");
+    deserializer.drop();
+
+    assert_eq!(&buffer[..len], b"cafe");
+}
+
+#[test]
+fn test_binary_bytes_roundtrip() {
+    let mut raw = [0u8; 32];
+    raw[..6].copy_from_slice(&[0xde, 0xad, 0x00, 0xbe, 0xef, 0xff]);
+
+    let mut serializer = JsonSerializer::new();
+    serializer.set_bytes_as_text(true);
+    serializer.serialize_struct_start("", 1).expect("Failed to start struct");
+    serializer.serialize_bytes("blob", &raw).expect("Failed to serialize");
+    serializer.serialize_struct_end().expect("Failed to end struct");
+    let json = serializer.print_unformatted().expect("Failed to print");
+
+    // Not valid UTF-8: written as an explicit array of numbers
+    assert!(json.starts_with(r#"{"blob":[222,173,0,190,239,255,0"#));
+
+    let mut deserializer = JsonDeserializer::parse(&json).expect("Failed to parse");
+    deserializer.set_bytes_as_text(true);
+    let mut buffer = [0u8; 32];
+    let len = deserializer.deserialize_bytes("blob", &mut buffer).expect("Failed to deserialize");
+    deserializer.drop();
+
+    assert_eq!(len, 32);
+    assert_eq!(buffer, raw);
+}
+
+#[test]
+fn test_number_array_bytes_roundtrip() {
+    let raw = [0xca, 0xfe, 0x00, 0x7f];
+
+    let mut serializer = JsonSerializer::new();
+    serializer.set_byte_encoding(ByteEncoding::Array);
+    serializer.serialize_struct_start("", 1).expect("Failed to start struct");
+    serializer.serialize_bytes("key", &raw).expect("Failed to serialize");
+    serializer.serialize_struct_end().expect("Failed to end struct");
+    let json = serializer.print_unformatted().expect("Failed to print");
+    serializer.reset();
+
+    assert_eq!(json, r#"{"key":[202,254,0,127]}"#);
+
+    let mut deserializer = JsonDeserializer::parse(&json).expect("Failed to parse");
+    let mut buffer = [0u8; 4];
+    let len = deserializer.deserialize_bytes("key", &mut buffer).expect("Failed to deserialize");
+    deserializer.drop();
+
+    assert_eq!(len, 4);
+    assert_eq!(buffer, raw);
+}
@@ -0,0 +1,55 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for Comparing Trees with Values
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+use cjson_binding::{CJson, cjson_eq_value};
+use osal_rs_serde::Serialize;
+
+#[derive(Serialize, Debug, Default)]
+struct Ntp {
+    port: u16,
+    enabled: bool,
+}
+
+#[derive(Serialize, Debug, Default)]
+struct Config {
+    version: u8,
+    ntp: Ntp,
+    ports: Vec<u16>,
+}
+
+#[test]
+fn test_cjson_eq_value() {
+    let config = Config {
+        version: 2,
+        ntp: Ntp { port: 123, enabled: true },
+        ports: vec![80, 443],
+    };
+
+    // Key order and whitespace do not matter
+    let doc = CJson::parse(r#"{ "ntp": {"enabled": true, "port": 123}, "version": 2, "ports": [80, 443] }"#)
+        .expect("Failed to parse");
+    assert_eq!(cjson_eq_value(&doc, &config), Ok(true));
+    doc.drop();
+
+    let doc = CJson::parse(r#"{"version":2,"ntp":{"port":124,"enabled":true},"ports":[80,443]}"#)
+        .expect("Failed to parse");
+    assert_eq!(cjson_eq_value(&doc, &config), Ok(false));
+    doc.drop();
+}
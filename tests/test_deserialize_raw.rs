@@ -0,0 +1,66 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for Raw Field Capture
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+use cjson_binding::CJson;
+use cjson_binding::de::JsonDeserializer;
+use osal_rs_serde::Deserializer;
+
+struct Device {
+    id: u32,
+    extra: CJson,
+}
+
+impl Device {
+    // Custom decoding: typed fields plus one opaque sub-document
+    fn decode(deserializer: &mut JsonDeserializer) -> Result<Self, cjson_binding::CJsonError> {
+        deserializer.deserialize_struct_start("")?;
+        let id = deserializer.deserialize_u32("id")?;
+        let extra = deserializer.deserialize_raw("extra")?;
+        deserializer.deserialize_struct_end()?;
+        Ok(Device { id, extra })
+    }
+}
+
+#[test]
+fn test_deserialize_raw_field() {
+    let json = r#"{"id":9,"extra":{"vendor":"acme","pins":[4,5]}}"#;
+
+    let mut deserializer = JsonDeserializer::parse(json).expect("Failed to parse");
+    let device = Device::decode(&mut deserializer).expect("Failed to deserialize");
+    // The captured copy outlives the deserializer
+    deserializer.drop();
+
+    assert_eq!(device.id, 9);
+    assert_eq!(
+        device.extra.print_unformatted().expect("Failed to print"),
+        r#"{"vendor":"acme","pins":[4,5]}"#
+    );
+    device.extra.drop();
+}
+
+#[test]
+fn test_deserialize_raw_current_node() {
+    let mut deserializer = JsonDeserializer::parse("[1,2,3]").expect("Failed to parse");
+    let raw = deserializer.deserialize_raw("").expect("Failed to capture");
+    deserializer.drop();
+
+    assert_eq!(raw.print_unformatted().expect("Failed to print"), "[1,2,3]");
+    raw.drop();
+}
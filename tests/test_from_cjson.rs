@@ -0,0 +1,52 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for Deserializing from CJson
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+use cjson_binding::{CJson, from_cjson};
+use osal_rs_serde::Deserialize;
+
+#[derive(Deserialize, Debug, Default, PartialEq)]
+struct Ntp {
+    port: u16,
+}
+
+#[derive(Deserialize, Debug, Default, PartialEq)]
+struct Config {
+    version: u8,
+    enabled: bool,
+    ntp: Ntp,
+}
+
+#[test]
+fn test_from_cjson() {
+    let mut ntp = CJson::create_object().expect("Failed to create object");
+    ntp.add_number_to_object("port", 123.0).expect("Failed to add");
+
+    let mut json = CJson::create_object().expect("Failed to create object");
+    json.add_number_to_object("version", 3.0).expect("Failed to add");
+    json.add_bool_to_object("enabled", true).expect("Failed to add");
+    json.add_item_to_object("ntp", ntp).expect("Failed to add");
+
+    let config: Config = from_cjson(&json).expect("Failed to deserialize");
+    assert_eq!(config, Config { version: 3, enabled: true, ntp: Ntp { port: 123 } });
+
+    // The source tree is still intact and owned by the caller
+    assert_eq!(json.print_unformatted().expect("Failed to print"), r#"{"version":3,"enabled":true,"ntp":{"port":123}}"#);
+    json.drop();
+}
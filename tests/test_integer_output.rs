@@ -0,0 +1,66 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for Integer Output
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+use cjson_binding::CJson;
+use cjson_binding::de::JsonDeserializer;
+use cjson_binding::ser::JsonSerializer;
+use osal_rs_serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Counters {
+    count: u32,
+    offset: i16,
+    uptime: u64,
+    balance: i64,
+}
+
+#[test]
+fn test_integers_print_as_integers() {
+    let counters = Counters { count: 42, offset: -3, uptime: 7, balance: -1 };
+
+    let mut serializer = JsonSerializer::new();
+    counters.serialize("", &mut serializer).expect("Failed to serialize");
+    let json = serializer.print_unformatted().expect("Failed to print");
+
+    assert!(json.contains(r#""count":42"#));
+    assert!(!json.contains("42.0"));
+    assert_eq!(json, r#"{"count":42,"offset":-3,"uptime":7,"balance":-1}"#);
+}
+
+#[test]
+fn test_large_integers_keep_every_digit() {
+    let counters = Counters { count: u32::MAX, offset: i16::MIN, uptime: u64::MAX, balance: i64::MIN + 1 };
+
+    let mut serializer = JsonSerializer::new();
+    counters.serialize("", &mut serializer).expect("Failed to serialize");
+    let json = serializer.print_unformatted().expect("Failed to print");
+
+    assert_eq!(
+        json,
+        r#"{"count":4294967295,"offset":-32768,"uptime":18446744073709551615,"balance":-9223372036854775807}"#
+    );
+
+    // cJSON parses numbers into f64; keeping them as text lets 64-bit fields round-trip
+    let tree = CJson::parse_preserve_numbers(&json).expect("Failed to parse");
+    let mut deserializer = JsonDeserializer::from_cjson(tree);
+    let decoded = Counters::deserialize(&mut deserializer, "").expect("Failed to deserialize");
+    deserializer.drop();
+    assert_eq!(decoded, counters);
+}
@@ -0,0 +1,76 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for Key Order
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+use cjson_binding::ser::JsonSerializer;
+use osal_rs_serde::{Serialize, Serializer};
+
+#[derive(Serialize, Debug)]
+struct Wifi {
+    password: String,
+    ssid: String,
+}
+
+#[derive(Serialize, Debug)]
+struct Config {
+    version: u8,
+    wifi: Wifi,
+    name: String,
+    zones: Vec<u8>,
+}
+
+fn config() -> Config {
+    Config {
+        version: 1,
+        wifi: Wifi { password: String::from("secret"), ssid: String::from("garden") },
+        name: String::from("pump"),
+        zones: vec![1, 2],
+    }
+}
+
+#[test]
+fn test_top_level_key_order() {
+    let mut serializer = JsonSerializer::new();
+    serializer.set_key_order(&["name", "version"]);
+    config().serialize("", &mut serializer).expect("Failed to serialize");
+    let json = serializer.print_unformatted().expect("Failed to print");
+
+    assert_eq!(json, r#"{"name":"pump","version":1,"wifi":{"password":"secret","ssid":"garden"},"zones":[1,2]}"#);
+}
+
+#[test]
+fn test_nested_objects_are_reordered() {
+    let mut serializer = JsonSerializer::new();
+    serializer.set_key_order(&["ssid", "zones", "missing"]);
+    config().serialize("", &mut serializer).expect("Failed to serialize");
+    let json = serializer.print_unformatted().expect("Failed to print");
+
+    assert_eq!(json, r#"{"zones":[1,2],"version":1,"wifi":{"ssid":"garden","password":"secret"},"name":"pump"}"#);
+}
+
+#[test]
+fn test_empty_order_keeps_declaration_order() {
+    let mut serializer = JsonSerializer::new();
+    serializer.set_key_order(&["name"]);
+    serializer.set_key_order(&[]);
+    config().serialize("", &mut serializer).expect("Failed to serialize");
+    let json = serializer.print_unformatted().expect("Failed to print");
+
+    assert_eq!(json, r#"{"version":1,"wifi":{"password":"secret","ssid":"garden"},"name":"pump","zones":[1,2]}"#);
+}
@@ -0,0 +1,73 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for Large Scalar Sequences
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+#[path = "../src/alloc_counter.rs"]
+mod alloc_counter;
+
+use alloc_counter::measure;
+use cjson_binding::ser::JsonSerializer;
+use osal_rs_serde::Serialize;
+
+#[derive(Serialize)]
+struct Batch {
+    samples: Vec<u32>,
+}
+
+/// `u64` elements may need Raw output, so they keep the generic per-element path
+#[derive(Serialize)]
+struct GenericBatch {
+    samples: Vec<u64>,
+}
+
+const LEN: u32 = 10_000;
+
+#[test]
+fn test_large_u32_vec() {
+    let batch = Batch { samples: (0..LEN).map(|i| i * 7).collect() };
+    let mut serializer = JsonSerializer::new();
+    let (ret, fast_stats) = measure(|| batch.serialize("", &mut serializer));
+    ret.expect("Failed to serialize");
+    let fast = serializer.print_unformatted().expect("Failed to print");
+    serializer.reset();
+
+    let generic = GenericBatch { samples: (0..LEN).map(|i| u64::from(i * 7)).collect() };
+    let (ret, generic_stats) = measure(|| generic.serialize("", &mut serializer));
+    ret.expect("Failed to serialize");
+    let expected = serializer.print_unformatted().expect("Failed to print");
+    serializer.reset();
+
+    assert_eq!(fast, expected);
+    assert!(fast.starts_with(r#"{"samples":[0,7,14,"#));
+    assert!(fast.ends_with(r#",69993]}"#));
+
+    // One node per element is the floor for both: root, array, its key and the elements
+    assert_eq!(fast_stats.c_allocs, LEN as usize + 3);
+    assert_eq!(generic_stats.c_allocs, fast_stats.c_allocs);
+    // The fast path never pushes the array on the serializer's stack
+    assert!(fast_stats.rust_allocs < generic_stats.rust_allocs);
+}
+
+#[test]
+fn test_empty_u32_vec_keeps_skip_empty() {
+    let mut serializer = JsonSerializer::new();
+    serializer.set_skip_empty(true);
+    Batch { samples: Vec::new() }.serialize("", &mut serializer).expect("Failed to serialize");
+    assert_eq!(serializer.print_unformatted().expect("Failed to print"), "{}");
+}
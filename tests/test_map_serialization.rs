@@ -0,0 +1,81 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for Map Serialization
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+use std::collections::BTreeMap;
+
+use cjson_binding::CJsonError;
+use cjson_binding::de::JsonDeserializer;
+use cjson_binding::ser::JsonSerializer;
+use osal_rs_serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Zone {
+    duration: u16,
+    enabled: bool,
+}
+
+#[test]
+fn test_integer_key_map_round_trip() {
+    let levels: BTreeMap<u32, u8> = [(1, 10), (2, 20), (10, 100)].into_iter().collect();
+
+    let mut serializer = JsonSerializer::new();
+    serializer.serialize_map("", &levels).expect("Failed to serialize");
+    let json = serializer.print_unformatted().expect("Failed to print");
+    assert_eq!(json, r#"{"1":10,"2":20,"10":100}"#);
+
+    let mut deserializer = JsonDeserializer::parse(&json).expect("Failed to parse");
+    let decoded: BTreeMap<u32, u8> = deserializer.deserialize_map("").expect("Failed to deserialize");
+    deserializer.drop();
+    assert_eq!(decoded, levels);
+}
+
+#[test]
+fn test_nested_struct_values() {
+    let mut zones = BTreeMap::new();
+    zones.insert(String::from("lawn"), Zone { duration: 600, enabled: true });
+    zones.insert(String::from("roses"), Zone { duration: 120, enabled: false });
+
+    let mut serializer = JsonSerializer::new();
+    serializer.serialize_struct_start("", 1).expect("Failed to start struct");
+    serializer.serialize_map("zones", &zones).expect("Failed to serialize");
+    serializer.serialize_struct_end().expect("Failed to end struct");
+    let json = serializer.print_unformatted().expect("Failed to print");
+    assert_eq!(
+        json,
+        r#"{"zones":{"lawn":{"duration":600,"enabled":true},"roses":{"duration":120,"enabled":false}}}"#
+    );
+
+    let mut deserializer = JsonDeserializer::parse(&json).expect("Failed to parse");
+    let decoded: BTreeMap<String, Zone> = deserializer.deserialize_map("zones").expect("Failed to deserialize");
+    deserializer.drop();
+    assert_eq!(decoded, zones);
+}
+
+#[test]
+fn test_invalid_integer_key() {
+    let mut deserializer = JsonDeserializer::parse(r#"{"1":1,"two":2}"#).expect("Failed to parse");
+    let result: Result<BTreeMap<u32, u8>, CJsonError> = deserializer.deserialize_map("");
+    assert_eq!(result, Err(CJsonError::UnexpectedType { expected: "integer key", found: "string" }));
+
+    // The deserializer is still usable afterwards
+    let retry: BTreeMap<String, u8> = deserializer.deserialize_map("").expect("Failed to deserialize");
+    assert_eq!(retry.len(), 2);
+    deserializer.drop();
+}
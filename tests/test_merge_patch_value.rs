@@ -0,0 +1,50 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for Typed Merge Patch
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+use cjson_binding::{CJson, CJsonError, merge_patch_value};
+use osal_rs_serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+struct Ntp {
+    port: u16,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+struct Config {
+    timezone: u16,
+    daylight_saving_time: bool,
+    ntp: Ntp,
+}
+
+#[test]
+fn test_merge_patch_value() {
+    let config = Config { timezone: 60, daylight_saving_time: true, ntp: Ntp { port: 123 } };
+
+    let patch = CJson::parse(r#"{"timezone":90}"#).expect("Failed to parse");
+    let merged = merge_patch_value(&config, &patch).expect("Failed to merge");
+    patch.drop();
+    assert_eq!(merged, Config { timezone: 90, ..config });
+
+    // A null removes the field, which Config cannot do without
+    let patch = CJson::parse(r#"{"timezone":90,"ntp":null}"#).expect("Failed to parse");
+    let err = merge_patch_value(&config, &patch).err();
+    patch.drop();
+    assert_eq!(err, Some(CJsonError::NotFound));
+}
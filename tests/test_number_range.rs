@@ -0,0 +1,89 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for Number Range Checks
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+use cjson_binding::{CJsonError, from_json};
+use cjson_binding::de::JsonDeserializer;
+use osal_rs_serde::{Deserialize, Deserializer};
+
+#[derive(Deserialize, Debug, Default)]
+struct Reading {
+    value: f32,
+}
+
+#[test]
+fn test_f32_overflow_is_rejected() {
+    let mut deserializer = JsonDeserializer::parse(r#"{"value":1e40}"#).expect("Failed to parse");
+    assert_eq!(
+        deserializer.deserialize_f32("value"),
+        Err(CJsonError::NumberOutOfRange { value: 1e40, min: f32::MIN as f64, max: f32::MAX as f64 })
+    );
+    deserializer.drop();
+
+    let ret: Result<Reading, _> = from_json(&r#"{"value":1e40}"#.to_string());
+    assert!(ret.is_err());
+}
+
+#[test]
+fn test_f32_in_range_values() {
+    let reading: Reading = from_json(&r#"{"value":0.0}"#.to_string()).expect("Failed to deserialize");
+    assert_eq!(reading.value, 0.0);
+
+    let reading: Reading = from_json(&r#"{"value":1e-40}"#.to_string()).expect("Failed to deserialize");
+    assert!(reading.value > 0.0 && reading.value < f32::MIN_POSITIVE);
+
+    let reading: Reading = from_json(&r#"{"value":-12.5}"#.to_string()).expect("Failed to deserialize");
+    assert_eq!(reading.value, -12.5);
+}
+
+#[test]
+fn test_integer_out_of_range_carries_bounds() {
+    let mut deserializer = JsonDeserializer::parse(r#"{"value":300}"#).expect("Failed to parse");
+    match deserializer.deserialize_u8("value") {
+        Err(CJsonError::NumberOutOfRange { value, min, max }) => {
+            assert_eq!(value, 300.0);
+            assert_eq!(min, 0.0);
+            assert_eq!(max, 255.0);
+        }
+        other => panic!("Expected NumberOutOfRange, got {:?}", other),
+    }
+    deserializer.drop();
+
+    let mut deserializer = JsonDeserializer::parse(r#"{"value":-1}"#).expect("Failed to parse");
+    assert_eq!(
+        deserializer.deserialize_u32("value"),
+        Err(CJsonError::NumberOutOfRange { value: -1.0, min: 0.0, max: u32::MAX as f64 })
+    );
+    deserializer.drop();
+}
+
+#[test]
+fn test_64_bit_upper_bounds_are_exclusive() {
+    // 2^64 and 2^63 are where `u64::MAX as f64` and `i64::MAX as f64` round to
+    let mut deserializer = JsonDeserializer::parse(r#"{"u":18446744073709551616,"i":9223372036854775808}"#)
+        .expect("Failed to parse");
+    assert!(matches!(deserializer.deserialize_u64("u"), Err(CJsonError::NumberOutOfRange { .. })));
+    assert!(matches!(deserializer.deserialize_i64("i"), Err(CJsonError::NumberOutOfRange { .. })));
+    deserializer.drop();
+
+    let mut deserializer = JsonDeserializer::parse(r#"{"u":1.5,"i":-9223372036854775808}"#).expect("Failed to parse");
+    assert_eq!(deserializer.deserialize_u64("u"), Ok(1));
+    assert_eq!(deserializer.deserialize_i64("i"), Ok(i64::MIN));
+    deserializer.drop();
+}
@@ -0,0 +1,59 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for Out-of-Range Integer Policies
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+use cjson_binding::CJsonError;
+use cjson_binding::de::{JsonDeserializer, NumberRange};
+use osal_rs_serde::Deserializer;
+
+fn read_u8(json: &str, policy: NumberRange) -> Result<u8, CJsonError> {
+    let mut deserializer = JsonDeserializer::parse(json).expect("Failed to parse");
+    deserializer.set_number_range(policy);
+    let ret = deserializer.deserialize_u8("level");
+    deserializer.drop();
+    ret
+}
+
+#[test]
+fn test_number_range_error_by_default() {
+    let mut deserializer = JsonDeserializer::parse(r#"{"level":300}"#).expect("Failed to parse");
+    let ret = deserializer.deserialize_u8("level");
+    deserializer.drop();
+
+    assert_eq!(ret, Err(CJsonError::NumberOutOfRange { value: 300.0, min: 0.0, max: 255.0 }));
+    assert_eq!(read_u8(r#"{"level":300}"#, NumberRange::Error), ret);
+}
+
+#[test]
+fn test_number_range_saturate() {
+    assert_eq!(read_u8(r#"{"level":300}"#, NumberRange::Saturate), Ok(255));
+    assert_eq!(read_u8(r#"{"level":-5}"#, NumberRange::Saturate), Ok(0));
+}
+
+#[test]
+fn test_number_range_wrap() {
+    assert_eq!(read_u8(r#"{"level":300}"#, NumberRange::Wrap), Ok(44));
+    assert_eq!(read_u8(r#"{"level":-1}"#, NumberRange::Wrap), Ok(255));
+
+    let mut deserializer = JsonDeserializer::parse(r#"{"offset":200}"#).expect("Failed to parse");
+    deserializer.set_number_range(NumberRange::Wrap);
+    let ret = deserializer.deserialize_i8("offset");
+    deserializer.drop();
+    assert_eq!(ret, Ok(200u8 as i8));
+}
@@ -0,0 +1,98 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for Custom Container Deserialization
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+use cjson_binding::CJsonResult;
+use cjson_binding::de::JsonDeserializer;
+use osal_rs_serde::Deserialize;
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+struct Reading {
+    sensor: u8,
+    value: u16,
+}
+
+/// Keeps only the last `N` readings of a JSON array of any length
+#[derive(Debug)]
+struct Window<const N: usize> {
+    readings: [Reading; N],
+    total: usize,
+}
+
+impl<const N: usize> Window<N> {
+    fn push(&mut self, reading: Reading) {
+        self.readings[self.total % N] = reading;
+        self.total += 1;
+    }
+
+    fn deserialize_json(deserializer: &mut JsonDeserializer, name: &str) -> CJsonResult<Self> {
+        let array = deserializer.deserialize_raw(name)?;
+        let mut window = Self { readings: [Reading::default(); N], total: 0 };
+        let ret = array.array_iter().and_then(|items| {
+            for item in items {
+                deserializer.push_element(&item)?;
+                let reading = Reading::deserialize(deserializer, "");
+                deserializer.pop_element()?;
+                window.push(reading?);
+            }
+            Ok(())
+        });
+        array.drop();
+        ret.map(|_| window)
+    }
+}
+
+#[test]
+fn test_custom_container_with_push_element() {
+    let json = r#"{"readings":[{"sensor":1,"value":10},{"sensor":2,"value":20},{"sensor":3,"value":30}]}"#;
+    let mut deserializer = JsonDeserializer::parse(json).expect("Failed to parse");
+    let window = Window::<2>::deserialize_json(&mut deserializer, "readings");
+
+    // The root is current again once every element is popped
+    let raw = deserializer.deserialize_raw("readings").expect("Failed to read root");
+    raw.drop();
+    assert!(deserializer.pop_element().is_err());
+    deserializer.drop();
+
+    let window = window.expect("Failed to deserialize");
+    assert_eq!(window.total, 3);
+    assert_eq!(window.readings[0], Reading { sensor: 3, value: 30 });
+    assert_eq!(window.readings[1], Reading { sensor: 2, value: 20 });
+}
+
+#[test]
+fn test_push_element_holding_arrays() {
+    let json = r#"{"batches":[[1,2,3],[4,5],[6]]}"#;
+    let mut deserializer = JsonDeserializer::parse(json).expect("Failed to parse");
+    let array = deserializer.deserialize_raw("batches").expect("Failed to read batches");
+
+    let mut batches = Vec::new();
+    for item in array.array_iter().expect("Not an array") {
+        deserializer.push_element(&item).expect("Failed to push");
+        // The element's own items are keyed "[0]", "[1]"... while it is pushed
+        let batch = Vec::<u16>::deserialize(&mut deserializer, "");
+        deserializer.pop_element().expect("Failed to pop");
+        batches.push(batch.expect("Failed to deserialize"));
+    }
+    array.drop();
+    assert!(deserializer.pop_element().is_err());
+    deserializer.drop();
+
+    assert_eq!(batches, vec![vec![1, 2, 3], vec![4, 5], vec![6]]);
+}
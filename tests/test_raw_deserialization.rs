@@ -0,0 +1,97 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for Raw Node Deserialization
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+use cjson_binding::{CJson, CJsonError};
+use cjson_binding::de::JsonDeserializer;
+use osal_rs_serde::{Deserialize, Deserializer};
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Sensor {
+    id: u64,
+    temperature: f64,
+    offset: i8,
+    enabled: bool,
+    label: String,
+}
+
+fn raw_sensor(label: &str) -> CJson {
+    let mut root = CJson::create_object().expect("Failed to create object");
+    root.add_item_to_object("id", CJson::create_raw("12345678901").unwrap()).unwrap();
+    root.add_item_to_object("temperature", CJson::create_raw("21.50").unwrap()).unwrap();
+    root.add_item_to_object("offset", CJson::create_raw("-3").unwrap()).unwrap();
+    root.add_item_to_object("enabled", CJson::create_raw("true").unwrap()).unwrap();
+    root.add_item_to_object("label", CJson::create_raw(label).unwrap()).unwrap();
+    root
+}
+
+#[test]
+fn test_raw_scalars_deserialize() {
+    let mut deserializer = JsonDeserializer::from_cjson(raw_sensor(r#""greenhouse""#));
+    let sensor = Sensor::deserialize(&mut deserializer, "").expect("Failed to deserialize");
+    deserializer.drop();
+
+    assert_eq!(sensor, Sensor {
+        id: 12_345_678_901,
+        temperature: 21.5,
+        offset: -3,
+        enabled: true,
+        label: String::from("greenhouse"),
+    });
+}
+
+#[test]
+fn test_preserved_numbers_deserialize() {
+    let json = CJson::parse_preserve_numbers(r#"{"id":7,"temperature":1.50,"offset":0,"enabled":false,"label":"x"}"#)
+        .expect("Failed to parse");
+    let mut deserializer = JsonDeserializer::from_cjson(json);
+    let sensor = Sensor::deserialize(&mut deserializer, "").expect("Failed to deserialize");
+    deserializer.drop();
+
+    assert_eq!(sensor.temperature, 1.5);
+    assert_eq!(sensor.id, 7);
+}
+
+#[test]
+fn test_raw_of_wrong_type() {
+    let mut deserializer = JsonDeserializer::from_cjson(raw_sensor("[1,2]"));
+    let result = Sensor::deserialize(&mut deserializer, "");
+    deserializer.drop();
+
+    assert_eq!(result, Err(CJsonError::UnexpectedType { expected: "string", found: "array" }));
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Totals {
+    energy: u64,
+    balance: i64,
+}
+
+#[test]
+fn test_raw_64_bit_integers_are_exact() {
+    // Both are beyond 2^53, where f64 can no longer hold every integer
+    let mut root = CJson::create_object().expect("Failed to create object");
+    root.add_item_to_object("energy", CJson::create_raw("9007199254740993").unwrap()).unwrap();
+    root.add_item_to_object("balance", CJson::create_raw("-9223372036854775807").unwrap()).unwrap();
+    let mut deserializer = JsonDeserializer::from_cjson(root);
+    let totals = Totals::deserialize(&mut deserializer, "").expect("Failed to deserialize");
+    deserializer.drop();
+
+    assert_eq!(totals, Totals { energy: 9_007_199_254_740_993, balance: i64::MIN + 1 });
+}
@@ -0,0 +1,45 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for Embedded CJson Fields
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+use cjson_binding::CJson;
+use cjson_binding::ser::JsonSerializer;
+use osal_rs_serde::Serializer;
+
+#[test]
+fn test_serialize_injected_cjson() {
+    let mut meta = CJson::create_object().expect("Failed to create object");
+    meta.add_string_to_object("vendor", "acme").expect("Failed to add");
+    meta.add_item_to_object("pins", CJson::create_int_array(&[4, 5]).expect("Failed to create array"))
+        .expect("Failed to add");
+
+    let mut serializer = JsonSerializer::new();
+    serializer.serialize_struct_start("", 2).expect("Failed to start struct");
+    serializer.serialize_u32("id", 9).expect("Failed to serialize");
+    serializer.serialize_cjson("meta", &meta).expect("Failed to serialize");
+    serializer.serialize_struct_end().expect("Failed to end struct");
+    let json = serializer.print_unformatted().expect("Failed to print");
+    serializer.reset();
+
+    assert_eq!(json, r#"{"id":9,"meta":{"vendor":"acme","pins":[4,5]}}"#);
+
+    // The caller's tree is untouched and still owned by the caller
+    assert_eq!(meta.print_unformatted().expect("Failed to print"), r#"{"vendor":"acme","pins":[4,5]}"#);
+    meta.drop();
+}
@@ -0,0 +1,53 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for Explicit Null Fields
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+use cjson_binding::CJson;
+use cjson_binding::from_json;
+use cjson_binding::ser::JsonSerializer;
+use osal_rs_serde::{Deserialize, Serializer};
+
+#[derive(Deserialize, Debug, Default, PartialEq)]
+struct Zone {
+    id: u8,
+    enabled: bool,
+}
+
+#[test]
+fn test_serialize_null_field() {
+    let mut serializer = JsonSerializer::new();
+    serializer.serialize_struct_start("", 3).expect("Failed to start struct");
+    serializer.serialize_u8("id", 4).expect("Failed to serialize");
+    serializer.serialize_null("schedule").expect("Failed to serialize");
+    serializer.serialize_bool("enabled", true).expect("Failed to serialize");
+    serializer.serialize_struct_end().expect("Failed to end struct");
+    let json = serializer.print_unformatted().expect("Failed to print");
+    serializer.reset();
+
+    assert_eq!(json, r#"{"id":4,"schedule":null,"enabled":true}"#);
+
+    // The key is present and null
+    let parsed = CJson::parse(&json).expect("Failed to parse");
+    assert!(parsed.get_object_item("schedule").expect("Missing key").is_null());
+    parsed.drop();
+
+    // The remaining fields still round-trip
+    let zone: Zone = from_json(&json).expect("Failed to deserialize");
+    assert_eq!(zone, Zone { id: 4, enabled: true });
+}
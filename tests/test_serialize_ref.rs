@@ -0,0 +1,62 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for Borrowed Field Serialization
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+use cjson_binding::to_json;
+use cjson_binding::ser::ByRef;
+use osal_rs_serde::Serialize;
+
+#[derive(Serialize, Debug, Default, Clone, Copy)]
+struct Valve {
+    pin: u8,
+    open: bool,
+}
+
+#[derive(Serialize, Debug, Default)]
+struct Owned {
+    main: Valve,
+    zones: Vec<Valve>,
+}
+
+#[derive(Serialize, Debug)]
+struct Borrowed<'a> {
+    main: ByRef<'a, Valve>,
+    zones: Vec<ByRef<'a, Valve>>,
+}
+
+#[test]
+fn test_borrowed_fields_match_owned() {
+    let main = Valve { pin: 4, open: true };
+    let zones = [Valve { pin: 5, open: false }, Valve { pin: 6, open: true }];
+
+    let owned = Owned { main, zones: zones.to_vec() };
+    let borrowed = Borrowed {
+        main: ByRef(&main),
+        zones: zones.iter().map(ByRef).collect(),
+    };
+
+    let owned_json = to_json(&owned).expect("Failed to serialize owned");
+    let borrowed_json = to_json(&borrowed).expect("Failed to serialize borrowed");
+
+    assert_eq!(borrowed_json, owned_json);
+    assert_eq!(
+        borrowed_json,
+        r#"{"main":{"pin":4,"open":true},"zones":[{"pin":5,"open":false},{"pin":6,"open":true}]}"#
+    );
+}
@@ -0,0 +1,57 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for Serializer Reuse
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+use cjson_binding::ser::JsonSerializer;
+use osal_rs_serde::Serialize;
+
+#[derive(Serialize, Debug, Default)]
+struct NtpConfig {
+    port: u16,
+    enabled: bool,
+}
+
+#[derive(Serialize, Debug, Default)]
+struct Sensor {
+    id: u32,
+    values: [u8; 2],
+}
+
+#[test]
+fn test_serializer_reuse() {
+    let mut serializer = JsonSerializer::new();
+
+    let ntp = NtpConfig { port: 123, enabled: true };
+    ntp.serialize("", &mut serializer).expect("Failed to serialize");
+    let first = serializer.print_unformatted().expect("Failed to print");
+    // Printing does not consume the tree
+    assert_eq!(serializer.print_unformatted().expect("Failed to print"), first);
+    assert_eq!(first, r#"{"port":123,"enabled":true}"#);
+
+    serializer.reset();
+
+    let sensor = Sensor { id: 7, values: [1, 2] };
+    sensor.serialize("", &mut serializer).expect("Failed to serialize");
+    let second = serializer.print_unformatted().expect("Failed to print");
+    // [u8; N] goes through the byte encoding, hex by default
+    assert_eq!(second, r#"{"id":7,"values":"0102"}"#);
+
+    serializer.reset();
+    assert!(serializer.print_unformatted().is_err());
+}
@@ -0,0 +1,67 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for Seeded Serializer Roots
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+use cjson_binding::CJson;
+use cjson_binding::CJsonError;
+use cjson_binding::ser::JsonSerializer;
+use osal_rs_serde::{Serialize, Serializer};
+
+#[derive(Serialize, Debug)]
+struct Reading {
+    temperature: i32,
+    humidity: u8,
+}
+
+#[test]
+fn test_serialize_into_seeded_root() {
+    let mut root = CJson::create_object().expect("Failed to create object");
+    root.add_string_to_object("device", "garden-01").expect("Failed to add field");
+
+    let mut serializer = JsonSerializer::with_root(root);
+    let reading = Reading { temperature: 21, humidity: 40 };
+    reading.serialize("", &mut serializer).expect("Failed to serialize");
+
+    let mut root = serializer.take_root().expect("Failed to take root");
+    assert_eq!(root.get_object_item("device").unwrap().get_string_value().unwrap(), "garden-01");
+    assert_eq!(root.get_object_item("temperature").unwrap().get_number_value().unwrap(), 21.0);
+
+    // The tree keeps growing by hand after the handoff
+    root.add_bool_to_object("valid", true).expect("Failed to add field");
+    let json = root.print_unformatted().expect("Failed to print");
+    root.drop();
+
+    assert_eq!(json, r#"{"device":"garden-01","temperature":21,"humidity":40,"valid":true}"#);
+}
+
+#[test]
+fn test_take_root_without_value() {
+    let serializer = JsonSerializer::new();
+    assert_eq!(serializer.take_root().err(), Some(CJsonError::NotFound));
+}
+
+#[test]
+fn test_seeded_root_must_be_object() {
+    let root = CJson::create_array().expect("Failed to create array");
+    let mut serializer = JsonSerializer::with_root(root);
+    let reading = Reading { temperature: 21, humidity: 40 };
+
+    assert_eq!(reading.serialize("", &mut serializer), Err(CJsonError::TypeError));
+    serializer.reset();
+}
@@ -0,0 +1,50 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for Typed Pointer Updates
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+use cjson_binding::{CJson, CJsonError, set_at_pointer};
+use osal_rs_serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+struct Ntp {
+    port: u16,
+    enabled: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+struct Config {
+    timezone: u16,
+    ntp: Ntp,
+}
+
+#[test]
+fn test_set_at_pointer() {
+    let config = Config { timezone: 60, ntp: Ntp { port: 123, enabled: true } };
+    let port = CJson::create_number(9999.0).expect("Failed to create number");
+
+    let updated = set_at_pointer(&config, "/ntp/port", &port).expect("Failed to set");
+    assert_eq!(updated.ntp.port, 9999);
+    assert_eq!(updated, Config { ntp: Ntp { port: 9999, ..config.ntp }, ..config });
+
+    // Not part of Config's schema
+    let err = set_at_pointer(&config, "/ntp/server", &port).err();
+    assert_eq!(err, Some(CJsonError::NotFound));
+
+    port.drop();
+}
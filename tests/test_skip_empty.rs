@@ -0,0 +1,59 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for Skipping Empty Containers
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+use cjson_binding::ser::JsonSerializer;
+use osal_rs_serde::Serialize;
+
+#[derive(Serialize, Debug, Default)]
+struct Schedule {
+    zones: Vec<u8>,
+}
+
+#[derive(Serialize, Debug, Default)]
+struct Program {
+    id: u8,
+    items: Vec<u16>,
+    schedule: Schedule,
+}
+
+fn serialize(program: &Program, skip_empty: bool) -> String {
+    let mut serializer = JsonSerializer::new();
+    serializer.set_skip_empty(skip_empty);
+    program.serialize("", &mut serializer).expect("Failed to serialize");
+    let json = serializer.print_unformatted().expect("Failed to print");
+    serializer.reset();
+    json
+}
+
+#[test]
+fn test_empty_containers_kept_by_default() {
+    let program = Program { id: 1, ..Default::default() };
+    assert_eq!(serialize(&program, false), r#"{"id":1,"items":[],"schedule":{"zones":[]}}"#);
+}
+
+#[test]
+fn test_skip_empty_containers() {
+    let program = Program { id: 1, ..Default::default() };
+    // The nested struct becomes empty once its empty Vec is dropped
+    assert_eq!(serialize(&program, true), r#"{"id":1}"#);
+
+    let program = Program { id: 2, items: vec![7], schedule: Schedule { zones: vec![3] } };
+    assert_eq!(serialize(&program, true), r#"{"id":2,"items":[7],"schedule":{"zones":[3]}}"#);
+}
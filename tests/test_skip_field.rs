@@ -0,0 +1,83 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for Skipped Fields
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+use cjson_binding::ser::JsonSerializer;
+use osal_rs_serde::{Serialize, Serializer};
+
+#[derive(Serialize, Debug, Default)]
+struct Credentials {
+    user: String,
+    password: String,
+}
+
+#[derive(Serialize, Debug, Default)]
+struct Device {
+    name: String,
+    port: u16,
+    credentials: Credentials,
+    tags: Vec<u16>,
+}
+
+#[test]
+fn test_skip_field_by_name() {
+    let device = Device {
+        name: String::from("garden"),
+        port: 8080,
+        credentials: Credentials { user: String::from("admin"), password: String::from("secret") },
+        tags: vec![1, 2],
+    };
+
+    let mut serializer = JsonSerializer::new();
+    serializer.set_skip_field(|name, _| name == "password");
+    device.serialize("", &mut serializer).expect("Failed to serialize");
+    let json = serializer.print_unformatted().expect("Failed to print");
+
+    assert_eq!(json, r#"{"name":"garden","port":8080,"credentials":{"user":"admin"},"tags":[1,2]}"#);
+}
+
+#[test]
+fn test_skip_field_by_value() {
+    let device = Device { name: String::from("garden"), port: 0, ..Default::default() };
+
+    let mut serializer = JsonSerializer::new();
+    // Drop zeros, empty strings and empty containers
+    serializer.set_skip_field(|_, node| {
+        (node.is_number() && node.get_number_value() == Ok(0.0))
+            || node.get_string_value().is_ok_and(|s| s.is_empty())
+            || node.get_array_size() == Ok(0)
+            || node.object_entries().is_ok_and(|entries| entries.is_empty())
+    });
+    device.serialize("", &mut serializer).expect("Failed to serialize");
+    let json = serializer.print_unformatted().expect("Failed to print");
+
+    assert_eq!(json, r#"{"name":"garden"}"#);
+}
+
+#[test]
+fn test_skip_field_ignores_array_elements() {
+    let device = Device { tags: vec![0, 7, 0], ..Default::default() };
+
+    let mut serializer = JsonSerializer::new();
+    serializer.set_skip_field(|name, _| name != "tags");
+    device.serialize("", &mut serializer).expect("Failed to serialize");
+    let json = serializer.print_unformatted().expect("Failed to print");
+
+    assert_eq!(json, r#"{"tags":[0,7,0]}"#);
+}
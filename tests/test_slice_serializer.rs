@@ -0,0 +1,150 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for Fixed Buffer Serialization
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+#[path = "../src/alloc_counter.rs"]
+mod alloc_counter;
+
+use alloc_counter::measure;
+use cjson_binding::slice::SliceSerializer;
+use cjson_binding::{CJsonError, to_json, to_slice};
+use osal_rs_serde::{Serialize, Serializer};
+
+#[derive(Serialize, Debug, Clone)]
+struct UserConfig {
+    user: u32,
+    password: u32,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct NtpConfig {
+    server: String,
+    port: u16,
+    enabled: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct Config {
+    version: u8,
+    timezone: i16,
+    ratio: f64,
+    ntp: NtpConfig,
+    users: [UserConfig; 2],
+    ports: Vec<u16>,
+    key: [u8; 4],
+}
+
+fn config() -> Config {
+    Config {
+        version: 1,
+        timezone: -60,
+        ratio: 0.25,
+        ntp: NtpConfig {
+            server: String::from("pool.ntp.org \"eu\"\n"),
+            port: 123,
+            enabled: true,
+        },
+        users: [
+            UserConfig { user: 100, password: 200 },
+            UserConfig { user: 300, password: 400 },
+        ],
+        ports: vec![80, 443, 8080],
+        key: [0xde, 0xad, 0xbe, 0xef],
+    }
+}
+
+/// Hand-written so the measurement only covers the serializer itself
+struct Reading {
+    sensor: &'static str,
+    value: f32,
+    samples: [u16; 3],
+}
+
+impl Serialize for Reading {
+    fn serialize<S: Serializer>(&self, name: &str, serializer: &mut S) -> Result<(), S::Error> {
+        serializer.serialize_struct_start(name, 3)?;
+        serializer.serialize_str("sensor", self.sensor)?;
+        serializer.serialize_f32("value", self.value)?;
+        serializer.serialize_array("samples", &self.samples)?;
+        serializer.serialize_struct_end()
+    }
+}
+
+#[test]
+fn test_slice_matches_tree_serializer() {
+    let config = config();
+    let expected = to_json(&config).expect("Failed to serialize");
+
+    let mut buffer = [0u8; 256];
+    let len = to_slice(&config, &mut buffer).expect("Failed to serialize");
+
+    assert_eq!(len, expected.len());
+    assert_eq!(&buffer[..len], expected.as_bytes());
+}
+
+#[test]
+fn test_slice_too_small() {
+    let config = config();
+    let expected = to_json(&config).expect("Failed to serialize");
+
+    let mut buffer = [0u8; 32];
+    let mut serializer: SliceSerializer = SliceSerializer::new(&mut buffer);
+    let result = config.serialize("", &mut serializer);
+
+    assert_eq!(result, Err(CJsonError::CapacityExceeded { capacity: 32 }));
+    // What fit stays in the buffer, but the document is not complete
+    assert!(expected.starts_with(serializer.as_str()));
+    assert_eq!(serializer.finish(), Err(CJsonError::InvalidOperation));
+}
+
+#[test]
+fn test_slice_depth_limit() {
+    let mut buffer = [0u8; 256];
+    let mut serializer: SliceSerializer<'_, 2> = SliceSerializer::new(&mut buffer);
+    let result = config().serialize("", &mut serializer);
+
+    // Root object, then `users` array, then its first object
+    assert_eq!(result, Err(CJsonError::DepthLimitExceeded { max: 2 }));
+}
+
+#[test]
+fn test_slice_does_not_allocate() {
+    let reading = Reading {
+        sensor: "soil",
+        value: 0.5,
+        samples: [10, 20, 30],
+    };
+    let mut buffer = [0u8; 64];
+
+    let (len, stats) = measure(|| to_slice(&reading, &mut buffer));
+    let len = len.expect("Failed to serialize");
+
+    assert_eq!(stats.rust_allocs, 0);
+    assert_eq!(stats.c_allocs, 0);
+    assert_eq!(&buffer[..len], br#"{"sensor":"soil","value":0.5,"samples":[10,20,30]}"#);
+}
+
+#[test]
+fn test_slice_root_must_be_struct() {
+    let mut buffer = [0u8; 64];
+    assert_eq!(to_slice(&[1u16, 2, 3], &mut buffer), Err(CJsonError::InvalidOperation));
+    assert_eq!(to_slice(&42u32, &mut buffer), Err(CJsonError::InvalidOperation));
+    // Same rule as the tree serializer
+    assert!(to_json(&42u32).is_err());
+}
@@ -0,0 +1,93 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for Streaming Serializer
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+use cjson_binding::stream::StreamSerializer;
+use cjson_binding::to_json;
+use osal_rs_serde::Serialize;
+
+#[derive(Serialize, Debug, Clone)]
+struct UserConfig {
+    user: u32,
+    password: u32,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct NtpConfig {
+    server: String,
+    port: u16,
+    enabled: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct Config {
+    version: u8,
+    timezone: i16,
+    ratio: f64,
+    ntp: NtpConfig,
+    users: [UserConfig; 2],
+    ports: Vec<u16>,
+    key: [u8; 4],
+}
+
+#[test]
+fn test_stream_matches_tree_serializer() {
+    let config = Config {
+        version: 1,
+        timezone: -60,
+        ratio: 0.1 + 0.2,
+        ntp: NtpConfig {
+            server: String::from("pool.ntp.org \"eu\"\n"),
+            port: 123,
+            enabled: true,
+        },
+        users: [
+            UserConfig { user: 100, password: 200 },
+            UserConfig { user: 300, password: 400 },
+        ],
+        ports: vec![80, 443, 8080],
+        key: [0xde, 0xad, 0xbe, 0xef],
+    };
+
+    let mut serializer = StreamSerializer::new(String::new());
+    config.serialize("", &mut serializer).expect("Failed to serialize");
+    let streamed = serializer.into_inner();
+
+    let expected = to_json(&config).expect("Failed to serialize");
+    assert_eq!(streamed, expected);
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct Counters {
+    uptime: u64,
+    offset: i64,
+    small: u64,
+}
+
+#[test]
+fn test_stream_writes_64_bit_integers_exactly() {
+    let counters = Counters { uptime: u64::MAX, offset: i64::MIN, small: 42 };
+
+    let mut serializer = StreamSerializer::new(String::new());
+    counters.serialize("", &mut serializer).expect("Failed to serialize");
+    let streamed = serializer.into_inner();
+
+    assert_eq!(streamed, r#"{"uptime":18446744073709551615,"offset":-9223372036854775808,"small":42}"#);
+    assert_eq!(streamed, to_json(&counters).expect("Failed to serialize"));
+}
@@ -0,0 +1,69 @@
+/***************************************************************************
+ *
+ * cJSON FFI BINDING FOR RUST - Test for Value Diffing and Patching
+ * Copyright (C) 2026 Antonio Salsi <passy.linux@zresa.it>
+ *
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library; if not, see <https://www.gnu.org/licenses/>.
+ *
+ ***************************************************************************/
+
+use cjson_binding::{CJson, CJsonError, apply_patch_to_value, diff_values};
+use osal_rs_serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+struct Config {
+    version: u8,
+    port: u16,
+    enabled: bool,
+}
+
+#[test]
+fn test_diff_values_single_replace() {
+    let old = Config { version: 1, port: 80, enabled: true };
+    let new = Config { port: 8080, ..old };
+
+    let patch = diff_values(&old, &new).expect("Failed to diff");
+    assert_eq!(patch.get_array_size().expect("Not an array"), 1);
+
+    let op = patch.get_array_item(0).expect("Missing op");
+    assert_eq!(op.get_object_item("op").unwrap().get_string_value().unwrap(), "replace");
+    assert_eq!(op.get_object_item("path").unwrap().get_string_value().unwrap(), "/port");
+    assert_eq!(op.get_object_item("value").unwrap().get_number_value().unwrap(), 8080.0);
+    patch.drop();
+
+    let same = diff_values(&old, &old).expect("Failed to diff");
+    assert_eq!(same.get_array_size().expect("Not an array"), 0);
+    same.drop();
+}
+
+#[test]
+fn test_apply_patch_to_value() {
+    let config = Config { version: 1, port: 80, enabled: true };
+
+    let patch = CJson::parse(r#"[{"op":"replace","path":"/port","value":8080}]"#).expect("Failed to parse");
+    let patched = apply_patch_to_value(&config, &patch).expect("Failed to apply");
+    patch.drop();
+    assert_eq!(patched, Config { port: 8080, ..config });
+
+    // Removing a field leaves a document that no longer fits Config
+    let patch = CJson::parse(r#"[{"op":"remove","path":"/enabled"}]"#).expect("Failed to parse");
+    let err = apply_patch_to_value(&config, &patch).err();
+    patch.drop();
+    assert_eq!(err, Some(CJsonError::NotFound));
+
+    let patch = CJson::parse(r#"[{"op":"replace","path":"/missing","value":1}]"#).expect("Failed to parse");
+    let err = apply_patch_to_value(&config, &patch).err();
+    patch.drop();
+    assert_eq!(err, Some(CJsonError::InvalidOperation));
+}